@@ -0,0 +1,284 @@
+use crate::tuple::{Tuple, EPSILON};
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+#[cfg(test)]
+use std::f64::consts::{PI, FRAC_1_SQRT_2, SQRT_2};
+
+// where the ray enters and leaves the slab between min and max along one axis
+pub fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> (f64, f64) {
+  let tmin_numerator = min - origin;
+  let tmax_numerator = max - origin;
+  let (tmin, tmax) = if direction.abs() >= EPSILON {
+    (tmin_numerator / direction, tmax_numerator / direction)
+  }
+  else {
+    (tmin_numerator * f64::INFINITY, tmax_numerator * f64::INFINITY)
+  };
+  if tmin > tmax {
+    (tmax, tmin)
+  }
+  else {
+    (tmin, tmax)
+  }
+}
+
+// axis aligned box, starts out inverted so that adding the first point snaps to it
+#[derive(Copy, Clone)]
+pub struct BoundingBox{ pub min: Tuple, pub max: Tuple }
+impl BoundingBox {
+  pub fn new(min: Tuple, max: Tuple) -> Self {
+    BoundingBox{min, max}
+  }
+  pub fn empty() -> Self {
+    BoundingBox{
+      min: Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+      max: Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY)
+    }
+  }
+  pub fn add_point(&mut self, point: Tuple) {
+    self.min = Tuple::point(self.min.x.min(point.x), self.min.y.min(point.y), self.min.z.min(point.z));
+    self.max = Tuple::point(self.max.x.max(point.x), self.max.y.max(point.y), self.max.z.max(point.z));
+  }
+  pub fn is_empty(&self) -> bool {
+    self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+  }
+  pub fn merge(&mut self, other: &BoundingBox) {
+    // an empty box's inverted corners would otherwise stretch this one out to infinity
+    if other.is_empty() {
+      return;
+    }
+    self.add_point(other.min);
+    self.add_point(other.max);
+  }
+  pub fn contains_point(&self, point: Tuple) -> bool {
+    self.min.x <= point.x && point.x <= self.max.x
+      && self.min.y <= point.y && point.y <= self.max.y
+      && self.min.z <= point.z && point.z <= self.max.z
+  }
+  pub fn contains_box(&self, other: &BoundingBox) -> bool {
+    self.contains_point(other.min) && self.contains_point(other.max)
+  }
+  // transforms the box one axis at a time (arvo's method), which also copes with
+  // infinite extents that would turn into NaN if the eight corners were multiplied out
+  pub fn transform(&self, m: &Matrix) -> BoundingBox {
+    let min = [self.min.x, self.min.y, self.min.z];
+    let max = [self.max.x, self.max.y, self.max.z];
+    let mut out_min = [0.0; 3];
+    let mut out_max = [0.0; 3];
+    for i in 0..3 {
+      out_min[i] = m.m[i][3];
+      out_max[i] = m.m[i][3];
+      for j in 0..3 {
+        let factor = m.m[i][j];
+        if factor == 0.0 {
+          continue;
+        }
+        let a = factor * min[j];
+        let b = factor * max[j];
+        out_min[i] += a.min(b);
+        out_max[i] += a.max(b);
+      }
+    }
+    BoundingBox::new(
+      Tuple::point(out_min[0], out_min[1], out_min[2]),
+      Tuple::point(out_max[0], out_max[1], out_max[2])
+    )
+  }
+  pub fn intersects(&self, ray: &Ray) -> bool {
+    let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, self.min.x, self.max.x);
+    let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, self.min.y, self.max.y);
+    let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, self.min.z, self.max.z);
+    let tmin = xtmin.max(ytmin).max(ztmin);
+    let tmax = xtmax.min(ytmax).min(ztmax);
+    tmin <= tmax
+  }
+  // halves the box across its longest axis
+  pub fn split(&self) -> (BoundingBox, BoundingBox) {
+    let dx = self.max.x - self.min.x;
+    let dy = self.max.y - self.min.y;
+    let dz = self.max.z - self.min.z;
+    let greatest = dx.max(dy).max(dz);
+    let (mut x0, mut y0, mut z0) = (self.min.x, self.min.y, self.min.z);
+    let (mut x1, mut y1, mut z1) = (self.max.x, self.max.y, self.max.z);
+    if greatest == dx {
+      x0 += dx / 2.0;
+      x1 = x0;
+    }
+    else if greatest == dy {
+      y0 += dy / 2.0;
+      y1 = y0;
+    }
+    else {
+      z0 += dz / 2.0;
+      z1 = z0;
+    }
+    let mid_min = Tuple::point(x0, y0, z0);
+    let mid_max = Tuple::point(x1, y1, z1);
+    (BoundingBox::new(self.min, mid_max), BoundingBox::new(mid_min, self.max))
+  }
+}
+
+#[test]
+fn creating_an_empty_bounding_box() {
+  let b = BoundingBox::empty();
+  assert_eq!(b.min.x, f64::INFINITY);
+  assert_eq!(b.max.x, f64::NEG_INFINITY);
+}
+#[test]
+fn creating_a_bounding_box_with_volume() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(3.0, 2.0, 1.0));
+  assert!(b.min.equals(Tuple::point(-1.0, -2.0, -3.0)));
+  assert!(b.max.equals(Tuple::point(3.0, 2.0, 1.0)));
+}
+#[test]
+fn adding_points_to_an_empty_bounding_box() {
+  let mut b = BoundingBox::empty();
+  b.add_point(Tuple::point(-5.0, 2.0, 0.0));
+  b.add_point(Tuple::point(7.0, 0.0, -3.0));
+  assert!(b.min.equals(Tuple::point(-5.0, 0.0, -3.0)));
+  assert!(b.max.equals(Tuple::point(7.0, 2.0, 0.0)));
+}
+#[test]
+fn adding_one_bounding_box_to_another() {
+  let mut b1 = BoundingBox::new(Tuple::point(-5.0, -2.0, 0.0), Tuple::point(7.0, 4.0, 4.0));
+  let b2 = BoundingBox::new(Tuple::point(8.0, -7.0, -2.0), Tuple::point(14.0, 2.0, 8.0));
+  b1.merge(&b2);
+  assert!(b1.min.equals(Tuple::point(-5.0, -7.0, -2.0)));
+  assert!(b1.max.equals(Tuple::point(14.0, 4.0, 8.0)));
+}
+#[test]
+fn adding_an_empty_bounding_box_changes_nothing() {
+  let mut b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+  b.merge(&BoundingBox::empty());
+  assert!(b.min.equals(Tuple::point(-1.0, -1.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(1.0, 1.0, 1.0)));
+  assert!(BoundingBox::empty().is_empty());
+  assert!(!b.is_empty());
+}
+#[test]
+fn checking_to_see_if_a_box_contains_a_given_point() {
+  let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+  let cases = [
+    (Tuple::point(5.0, -2.0, 0.0), true),
+    (Tuple::point(11.0, 4.0, 7.0), true),
+    (Tuple::point(8.0, 1.0, 3.0), true),
+    (Tuple::point(3.0, 0.0, 3.0), false),
+    (Tuple::point(8.0, -4.0, 3.0), false),
+    (Tuple::point(8.0, 1.0, -1.0), false),
+    (Tuple::point(13.0, 1.0, 3.0), false),
+    (Tuple::point(8.0, 5.0, 3.0), false),
+    (Tuple::point(8.0, 1.0, 8.0), false)
+  ];
+  for (point, result) in cases {
+    assert_eq!(b.contains_point(point), result);
+  }
+}
+#[test]
+fn checking_to_see_if_a_box_contains_a_given_box() {
+  let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+  let cases = [
+    (Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0), true),
+    (Tuple::point(6.0, -1.0, 1.0), Tuple::point(10.0, 3.0, 6.0), true),
+    (Tuple::point(4.0, -3.0, -1.0), Tuple::point(10.0, 3.0, 6.0), false),
+    (Tuple::point(6.0, -1.0, 1.0), Tuple::point(12.0, 5.0, 8.0), false)
+  ];
+  for (min, max, result) in cases {
+    assert_eq!(b.contains_box(&BoundingBox::new(min, max)), result);
+  }
+}
+#[test]
+fn transforming_a_bounding_box() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+  let m = Matrix::rotation_x(PI/4.0) * Matrix::rotation_y(PI/4.0);
+  let b2 = b.transform(&m);
+  assert!(b2.min.equals(Tuple::point(-SQRT_2, -(1.0 + FRAC_1_SQRT_2), -(1.0 + FRAC_1_SQRT_2))));
+  assert!(b2.max.equals(Tuple::point(SQRT_2, 1.0 + FRAC_1_SQRT_2, 1.0 + FRAC_1_SQRT_2)));
+}
+#[test]
+fn transforming_an_infinite_bounding_box_keeps_it_finite_where_it_should_be() {
+  let b = BoundingBox::new(
+    Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+    Tuple::point(f64::INFINITY, 0.0, f64::INFINITY)
+  );
+  let b2 = b.transform(&Matrix::translation(0.0, 2.0, 0.0));
+  assert_eq!(b2.min.y, 2.0);
+  assert_eq!(b2.max.y, 2.0);
+  assert_eq!(b2.min.x, f64::NEG_INFINITY);
+  assert_eq!(b2.max.z, f64::INFINITY);
+}
+#[test]
+fn intersecting_a_ray_with_a_bounding_box_at_the_origin() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+  let cases = [
+    (Tuple::point(5.0, 0.5, 0.0), Tuple::vector(-1.0, 0.0, 0.0), true),
+    (Tuple::point(-5.0, 0.5, 0.0), Tuple::vector(1.0, 0.0, 0.0), true),
+    (Tuple::point(0.5, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), true),
+    (Tuple::point(0.5, -5.0, 0.0), Tuple::vector(0.0, 1.0, 0.0), true),
+    (Tuple::point(0.5, 0.0, 5.0), Tuple::vector(0.0, 0.0, -1.0), true),
+    (Tuple::point(0.5, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), true),
+    (Tuple::point(0.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0), true),
+    (Tuple::point(-2.0, 0.0, 0.0), Tuple::vector(2.0, 4.0, 6.0), false),
+    (Tuple::point(0.0, -2.0, 0.0), Tuple::vector(6.0, 2.0, 4.0), false),
+    (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(4.0, 6.0, 2.0), false),
+    (Tuple::point(2.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, -1.0), false),
+    (Tuple::point(0.0, 2.0, 2.0), Tuple::vector(0.0, -1.0, 0.0), false),
+    (Tuple::point(2.0, 2.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0), false)
+  ];
+  for (origin, direction, result) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    assert_eq!(b.intersects(&r), result);
+  }
+}
+#[test]
+fn intersecting_a_ray_with_a_noncubic_bounding_box() {
+  let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+  let cases = [
+    (Tuple::point(15.0, 1.0, 2.0), Tuple::vector(-1.0, 0.0, 0.0), true),
+    (Tuple::point(-5.0, -1.0, 4.0), Tuple::vector(1.0, 0.0, 0.0), true),
+    (Tuple::point(7.0, 6.0, 5.0), Tuple::vector(0.0, -1.0, 0.0), true),
+    (Tuple::point(9.0, -5.0, 6.0), Tuple::vector(0.0, 1.0, 0.0), true),
+    (Tuple::point(8.0, 2.0, 12.0), Tuple::vector(0.0, 0.0, -1.0), true),
+    (Tuple::point(6.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), true),
+    (Tuple::point(8.0, 1.0, 3.5), Tuple::vector(0.0, 0.0, 1.0), true),
+    (Tuple::point(9.0, -1.0, -8.0), Tuple::vector(2.0, 4.0, 6.0), false),
+    (Tuple::point(8.0, 3.0, -4.0), Tuple::vector(6.0, 2.0, 4.0), false),
+    (Tuple::point(9.0, -1.0, -2.0), Tuple::vector(4.0, 6.0, 2.0), false),
+    (Tuple::point(4.0, 0.0, 9.0), Tuple::vector(0.0, 0.0, -1.0), false),
+    (Tuple::point(8.0, 6.0, -1.0), Tuple::vector(0.0, -1.0, 0.0), false),
+    (Tuple::point(12.0, 5.0, 4.0), Tuple::vector(-1.0, 0.0, 0.0), false)
+  ];
+  for (origin, direction, result) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    assert_eq!(b.intersects(&r), result);
+  }
+}
+#[test]
+fn splitting_a_perfect_cube() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -4.0, -5.0), Tuple::point(9.0, 6.0, 5.0));
+  let (left, right) = b.split();
+  assert!(left.min.equals(Tuple::point(-1.0, -4.0, -5.0)));
+  assert!(left.max.equals(Tuple::point(4.0, 6.0, 5.0)));
+  assert!(right.min.equals(Tuple::point(4.0, -4.0, -5.0)));
+  assert!(right.max.equals(Tuple::point(9.0, 6.0, 5.0)));
+}
+#[test]
+fn splitting_an_x_wide_box() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(9.0, 5.5, 3.0));
+  let (left, right) = b.split();
+  assert!(left.max.equals(Tuple::point(4.0, 5.5, 3.0)));
+  assert!(right.min.equals(Tuple::point(4.0, -2.0, -3.0)));
+}
+#[test]
+fn splitting_a_y_wide_box() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(5.0, 8.0, 3.0));
+  let (left, right) = b.split();
+  assert!(left.max.equals(Tuple::point(5.0, 3.0, 3.0)));
+  assert!(right.min.equals(Tuple::point(-1.0, 3.0, -3.0)));
+}
+#[test]
+fn splitting_a_z_wide_box() {
+  let b = BoundingBox::new(Tuple::point(-1.0, -2.0, -3.0), Tuple::point(5.0, 3.0, 7.0));
+  let (left, right) = b.split();
+  assert!(left.max.equals(Tuple::point(5.0, 3.0, 2.0)));
+  assert!(right.min.equals(Tuple::point(-1.0, -2.0, 2.0)));
+}
@@ -0,0 +1,297 @@
+use std::ops::Range;
+use crate::tuple::Tuple;
+use crate::ray::Ray;
+use crate::bounds::BoundingBox;
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use crate::shape::Shape;
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::group::Group;
+
+// centroids are binned into this many buckets along the split axis
+const BUCKETS: usize = 12;
+// nodes with this many primitives or fewer may become leaves when splitting doesn't pay
+const MAX_LEAF_SIZE: usize = 4;
+// cost of visiting an interior node relative to intersecting one primitive
+const TRAVERSAL_COST: f64 = 0.125;
+
+// one entry of the flattened tree. a leaf (count > 0) covers primitives offset..offset + count,
+// an interior node (count == 0) has its first child right after it and its second at offset
+#[derive(Copy, Clone)]
+pub struct BvhNode{ pub bounds: BoundingBox, pub offset: usize, pub count: usize, pub axis: usize }
+impl BvhNode {
+  pub fn is_leaf(&self) -> bool {
+    self.count > 0
+  }
+}
+
+pub struct Bvh{ pub nodes: Vec<BvhNode> }
+
+struct BuildItem{ index: usize, bounds: BoundingBox, centroid: Tuple }
+
+fn axis_value(t: Tuple, axis: usize) -> f64 {
+  match axis {
+    0 => t.x,
+    1 => t.y,
+    _ => t.z
+  }
+}
+
+fn surface_area(b: &BoundingBox) -> f64 {
+  let d = b.max - b.min;
+  if d.x < 0.0 || d.y < 0.0 || d.z < 0.0 {
+    return 0.0;
+  }
+  2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+}
+
+fn longest_axis(b: &BoundingBox) -> usize {
+  let d = b.max - b.min;
+  if d.x >= d.y && d.x >= d.z {
+    0
+  }
+  else if d.y >= d.z {
+    1
+  }
+  else {
+    2
+  }
+}
+
+// partitions items in place at the cheapest bucket boundary and returns where the
+// right half starts, or None when no boundary separates them or a leaf is cheaper
+fn sah_split(items: &mut [BuildItem], bounds: &BoundingBox, centroid_bounds: &BoundingBox, axis: usize) -> Option<usize> {
+  let min = axis_value(centroid_bounds.min, axis);
+  let extent = axis_value(centroid_bounds.max, axis) - min;
+  if !(extent > 0.0 && extent.is_finite()) {
+    return None;
+  }
+  let bucket_of = |item: &BuildItem| {
+    let b = ((axis_value(item.centroid, axis) - min) / extent * BUCKETS as f64) as usize;
+    b.min(BUCKETS - 1)
+  };
+  let mut counts = [0usize; BUCKETS];
+  let mut boxes = [BoundingBox::empty(); BUCKETS];
+  for item in items.iter() {
+    let b = bucket_of(item);
+    counts[b] += 1;
+    boxes[b].merge(&item.bounds);
+  }
+  let total_area = surface_area(bounds);
+  let mut best: Option<(usize, f64)> = None;
+  for split in 0..BUCKETS - 1 {
+    let (mut left, mut right) = (BoundingBox::empty(), BoundingBox::empty());
+    let (mut left_count, mut right_count) = (0, 0);
+    for b in 0..=split {
+      left.merge(&boxes[b]);
+      left_count += counts[b];
+    }
+    for b in split + 1..BUCKETS {
+      right.merge(&boxes[b]);
+      right_count += counts[b];
+    }
+    if left_count == 0 || right_count == 0 {
+      continue;
+    }
+    let cost = TRAVERSAL_COST
+      + (left_count as f64 * surface_area(&left) + right_count as f64 * surface_area(&right)) / total_area;
+    if cost < best.map_or(f64::INFINITY, |(_, c)| c) {
+      best = Some((split, cost));
+    }
+  }
+  let (split, cost) = best?;
+  if items.len() <= MAX_LEAF_SIZE && cost >= items.len() as f64 {
+    return None;
+  }
+  // stable two-way partition around the chosen boundary
+  items.sort_by_key(|item| bucket_of(item) > split);
+  Some(items.iter().take_while(|item| bucket_of(item) <= split).count())
+}
+
+// appends the subtree for items, whose primitives start at first, and returns its index
+fn build_recursive(items: &mut [BuildItem], first: usize, nodes: &mut Vec<BvhNode>) -> usize {
+  let node_index = nodes.len();
+  let mut bounds = BoundingBox::empty();
+  let mut centroid_bounds = BoundingBox::empty();
+  for item in items.iter() {
+    bounds.merge(&item.bounds);
+    centroid_bounds.add_point(item.centroid);
+  }
+  nodes.push(BvhNode{bounds, offset: first, count: items.len(), axis: 0});
+  if items.len() == 1 {
+    return node_index;
+  }
+  let axis = longest_axis(&centroid_bounds);
+  let mid = match sah_split(items, &bounds, &centroid_bounds, axis) {
+    Some(mid) => mid,
+    None if items.len() <= MAX_LEAF_SIZE => return node_index,
+    // everything shares a centroid (or bounds are unbounded), halve the list so the tree still terminates
+    None => {
+      items.sort_by(|a, b| axis_value(a.centroid, axis).total_cmp(&axis_value(b.centroid, axis)));
+      items.len() / 2
+    }
+  };
+  let (left, right) = items.split_at_mut(mid);
+  build_recursive(left, first, nodes);
+  let second = build_recursive(right, first + mid, nodes);
+  nodes[node_index] = BvhNode{bounds, offset: second, count: 0, axis};
+  node_index
+}
+
+impl Bvh {
+  // builds over the given primitive bounds, also returning the order the primitives
+  // must be stored in so that every leaf covers a contiguous range
+  pub fn build(bounds: &[BoundingBox]) -> (Bvh, Vec<usize>) {
+    let mut items: Vec<BuildItem> = bounds.iter().enumerate().map(|(index, b)| {
+      BuildItem{index, bounds: *b, centroid: b.min * 0.5 + b.max * 0.5}
+    }).collect();
+    let mut nodes = vec![];
+    if !items.is_empty() {
+      build_recursive(&mut items, 0, &mut nodes);
+    }
+    let order = items.iter().map(|item| item.index).collect();
+    (Bvh{nodes}, order)
+  }
+  // calls visit with the primitive range of every leaf the ray's path crosses,
+  // descending into the child nearer the ray origin first. nothing is skipped on account
+  // of that order, every crossed leaf is visited
+  pub fn traverse<F: FnMut(Range<usize>)>(&self, ray: &Ray, mut visit: F) {
+    if self.nodes.is_empty() {
+      return;
+    }
+    let dir_is_neg = [ray.direction.x < 0.0, ray.direction.y < 0.0, ray.direction.z < 0.0];
+    let mut stack = vec![0];
+    while let Some(index) = stack.pop() {
+      let node = &self.nodes[index];
+      if !node.bounds.intersects(ray) {
+        continue;
+      }
+      if node.is_leaf() {
+        visit(node.offset..node.offset + node.count);
+      }
+      else if dir_is_neg[node.axis] {
+        stack.push(index + 1);
+        stack.push(node.offset);
+      }
+      else {
+        stack.push(node.offset);
+        stack.push(index + 1);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+fn unit_box_at(x: f64, y: f64, z: f64) -> BoundingBox {
+  BoundingBox::new(Tuple::point(x - 0.5, y - 0.5, z - 0.5), Tuple::point(x + 0.5, y + 0.5, z + 0.5))
+}
+
+#[test]
+fn building_over_nothing_gives_an_empty_tree() {
+  let (bvh, order) = Bvh::build(&[]);
+  assert!(bvh.nodes.is_empty());
+  assert!(order.is_empty());
+}
+#[test]
+fn a_single_primitive_is_a_leaf() {
+  let (bvh, order) = Bvh::build(&[unit_box_at(0.0, 0.0, 0.0)]);
+  assert_eq!(bvh.nodes.len(), 1);
+  assert!(bvh.nodes[0].is_leaf());
+  assert_eq!(order, vec![0]);
+}
+#[test]
+fn every_primitive_lands_in_exactly_one_leaf_that_bounds_it() {
+  let boxes: Vec<BoundingBox> = (0..50).map(|i| unit_box_at((i * 7 % 13) as f64, (i * 3 % 5) as f64, i as f64)).collect();
+  let (bvh, order) = Bvh::build(&boxes);
+  let mut seen = vec![0; boxes.len()];
+  for node in bvh.nodes.iter().filter(|n| n.is_leaf()) {
+    for slot in node.offset..node.offset + node.count {
+      let b = &boxes[order[slot]];
+      seen[order[slot]] += 1;
+      assert!(node.bounds.contains_box(b));
+    }
+  }
+  assert!(seen.iter().all(|&n| n == 1));
+}
+#[test]
+fn interior_nodes_store_their_first_child_next_to_them() {
+  let boxes: Vec<BoundingBox> = (0..16).map(|i| unit_box_at(i as f64 * 2.0, 0.0, 0.0)).collect();
+  let (bvh, _) = Bvh::build(&boxes);
+  let root = bvh.nodes[0];
+  assert!(!root.is_leaf());
+  assert_eq!(root.axis, 0);
+  let first = bvh.nodes[1];
+  let second = bvh.nodes[root.offset];
+  assert!(root.bounds.contains_box(&first.bounds));
+  assert!(root.bounds.contains_box(&second.bounds));
+  assert!(first.bounds.max.x <= second.bounds.min.x);
+}
+#[test]
+fn the_surface_area_heuristic_isolates_a_distant_primitive() {
+  // a midpoint split would cut the empty space in half and leave the cluster together anyway,
+  // sah should put the lone far box in a leaf of its own right at the root
+  let mut boxes: Vec<BoundingBox> = (0..8).map(|i| unit_box_at(i as f64 * 0.1, 0.0, 0.0)).collect();
+  boxes.push(unit_box_at(100.0, 0.0, 0.0));
+  let (bvh, order) = Bvh::build(&boxes);
+  let second = bvh.nodes[bvh.nodes[0].offset];
+  assert!(second.is_leaf());
+  assert_eq!(second.count, 1);
+  assert_eq!(order[second.offset], 8);
+}
+#[test]
+fn identical_primitives_still_terminate() {
+  let boxes = vec![unit_box_at(1.0, 1.0, 1.0); 20];
+  let (bvh, order) = Bvh::build(&boxes);
+  assert_eq!(order.len(), 20);
+  assert!(bvh.nodes.iter().filter(|n| n.is_leaf()).all(|n| n.count <= MAX_LEAF_SIZE));
+}
+#[test]
+fn traversal_visits_leaves_front_to_back() {
+  let boxes: Vec<BoundingBox> = (0..8).map(|i| unit_box_at(i as f64 * 4.0, 0.0, 0.0)).collect();
+  let (bvh, order) = Bvh::build(&boxes);
+  // order within a leaf is whatever the build left behind, so compare the leaves' nearest boxes
+  let mut visited = vec![];
+  let r = Ray::new(Tuple::point(-10.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+  bvh.traverse(&r, |range| visited.push(range.map(|slot| order[slot]).min().unwrap()));
+  assert!(visited.len() > 1);
+  assert!(visited.windows(2).all(|w| w[0] < w[1]));
+  let mut visited = vec![];
+  let r = Ray::new(Tuple::point(50.0, 0.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0));
+  bvh.traverse(&r, |range| visited.push(range.map(|slot| order[slot]).max().unwrap()));
+  assert!(visited.len() > 1);
+  assert!(visited.windows(2).all(|w| w[0] > w[1]));
+}
+#[test]
+fn traversal_skips_leaves_the_ray_misses() {
+  let boxes: Vec<BoundingBox> = (0..8).map(|i| unit_box_at(i as f64 * 4.0, 0.0, 0.0)).collect();
+  let (bvh, order) = Bvh::build(&boxes);
+  let mut visited = vec![];
+  let r = Ray::new(Tuple::point(8.0, -10.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+  bvh.traverse(&r, |range| visited.extend(range.map(|slot| order[slot])));
+  assert!(visited.contains(&2));
+  assert!(visited.len() < 8);
+}
+#[test]
+fn a_group_with_a_bvh_finds_the_same_hits_as_brute_force() {
+  let make = || {
+    let mut g = Group::new();
+    for i in 0..64 {
+      let mut s = Sphere::new();
+      s.set_transform(Matrix::translation((i % 8) as f64 * 2.5, (i / 8) as f64 * 2.5, (i % 3) as f64));
+      g.add_child(Box::new(s));
+    }
+    g
+  };
+  let plain = make();
+  let mut accelerated = make();
+  accelerated.build_bvh();
+  for i in 0..20 {
+    let r = Ray::new(Tuple::point(i as f64, 9.0, -10.0), Tuple::vector(0.1, -0.3, 1.0).normalize());
+    let expected: Vec<f64> = plain.intersect(&r).into_iter().map(|x| x.t).collect();
+    let actual: Vec<f64> = accelerated.intersect(&r).into_iter().map(|x| x.t).collect();
+    assert_eq!(expected, actual);
+  }
+}
@@ -0,0 +1,143 @@
+use crate::tuple::Tuple;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::canvas::Canvas;
+use crate::world::{World, MAX_DEPTH};
+#[cfg(test)]
+use std::f64::consts::{PI, FRAC_1_SQRT_2};
+#[cfg(test)]
+use crate::world::default_world;
+
+pub struct Camera{
+  pub hsize: usize,
+  pub vsize: usize,
+  pub field_of_view: f64,
+  transform: Matrix,
+  inverse: Matrix,
+  pixel_size: f64,
+  half_width: f64,
+  half_height: f64
+}
+impl Camera {
+  pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+    // the canvas sits one unit in front of the camera, so half_view is half its wider side
+    let half_view = (field_of_view / 2.0).tan();
+    let aspect = hsize as f64 / vsize as f64;
+    let (half_width, half_height) = if aspect >= 1.0 {
+      (half_view, half_view / aspect)
+    }
+    else {
+      (half_view * aspect, half_view)
+    };
+    let pixel_size = (half_width * 2.0) / hsize as f64;
+    Camera{
+      hsize,
+      vsize,
+      field_of_view,
+      transform: Matrix::identity(),
+      inverse: Matrix::identity(),
+      pixel_size,
+      half_width,
+      half_height
+    }
+  }
+  pub fn transform(&self) -> &Matrix {
+    &self.transform
+  }
+  pub fn set_transform(&mut self, transform: Matrix) {
+    self.inverse = transform.inverse();
+    self.transform = transform;
+  }
+  pub fn pixel_size(&self) -> f64 {
+    self.pixel_size
+  }
+  pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
+    // offset from the edge of the canvas to the pixel's center
+    let xoffset = (px as f64 + 0.5) * self.pixel_size;
+    let yoffset = (py as f64 + 0.5) * self.pixel_size;
+    // the camera looks toward -z, so +x is to the left
+    let world_x = self.half_width - xoffset;
+    let world_y = self.half_height - yoffset;
+    let pixel = &self.inverse * Tuple::point(world_x, world_y, -1.0);
+    let origin = &self.inverse * Tuple::point(0.0, 0.0, 0.0);
+    let direction = (pixel - origin).normalize();
+    Ray::new(origin, direction)
+  }
+  pub fn render(&self, world: &World) -> Canvas {
+    let mut image = Canvas::new(self.hsize, self.vsize);
+    self.render_into(world, &mut image);
+    image
+  }
+  // fills the top left hsize by vsize pixels of an existing canvas, which must be at least that big
+  pub fn render_into(&self, world: &World, canvas: &mut Canvas) {
+    for y in 0..self.vsize {
+      for x in 0..self.hsize {
+        let ray = self.ray_for_pixel(x, y);
+        canvas.write_pixel(x as f64, y as f64, world.color_at(&ray, MAX_DEPTH));
+      }
+    }
+  }
+}
+
+#[test]
+fn constructing_a_camera() {
+  let c = Camera::new(160, 120, PI/2.0);
+  assert_eq!(c.hsize, 160);
+  assert_eq!(c.vsize, 120);
+  assert_eq!(c.field_of_view, PI/2.0);
+  assert!(c.transform().equals(Matrix::identity()));
+}
+#[test]
+fn the_pixel_size_for_a_horizontal_canvas() {
+  let c = Camera::new(200, 125, PI/2.0);
+  assert!((c.pixel_size() - 0.01).abs() < 0.00001);
+}
+#[test]
+fn the_pixel_size_for_a_vertical_canvas() {
+  let c = Camera::new(125, 200, PI/2.0);
+  assert!((c.pixel_size() - 0.01).abs() < 0.00001);
+}
+#[test]
+fn constructing_a_ray_through_the_center_of_the_canvas() {
+  let c = Camera::new(201, 101, PI/2.0);
+  let r = c.ray_for_pixel(100, 50);
+  assert!(r.origin.equals(Tuple::point(0.0, 0.0, 0.0)));
+  assert!(r.direction.equals(Tuple::vector(0.0, 0.0, -1.0)));
+}
+#[test]
+fn constructing_a_ray_through_a_corner_of_the_canvas() {
+  let c = Camera::new(201, 101, PI/2.0);
+  let r = c.ray_for_pixel(0, 0);
+  assert!(r.origin.equals(Tuple::point(0.0, 0.0, 0.0)));
+  assert!(r.direction.equals(Tuple::vector(0.66519, 0.33259, -0.66851)));
+}
+#[test]
+fn constructing_a_ray_when_the_camera_is_transformed() {
+  let mut c = Camera::new(201, 101, PI/2.0);
+  c.set_transform(Matrix::rotation_y(PI/4.0) * Matrix::translation(0.0, -2.0, 5.0));
+  let r = c.ray_for_pixel(100, 50);
+  assert!(r.origin.equals(Tuple::point(0.0, 2.0, -5.0)));
+  assert!(r.direction.equals(Tuple::vector(FRAC_1_SQRT_2, 0.0, -FRAC_1_SQRT_2)));
+}
+#[test]
+fn rendering_a_world_with_a_camera() {
+  let w = default_world();
+  let mut c = Camera::new(11, 11, PI/2.0);
+  let from = Tuple::point(0.0, 0.0, -5.0);
+  let to = Tuple::point(0.0, 0.0, 0.0);
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+  c.set_transform(Matrix::view_transform(from, to, up));
+  let image = c.render(&w);
+  assert!(image.pixel_at(5, 5).equals(Tuple::color(0.38066, 0.47583, 0.2855)));
+}
+#[test]
+fn rendering_into_an_existing_canvas() {
+  let w = default_world();
+  let mut c = Camera::new(11, 11, PI/2.0);
+  c.set_transform(Matrix::view_transform(Tuple::point(0.0, 0.0, -5.0), Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)));
+  let mut image = Canvas::new(11, 11);
+  image.fill_with(Tuple::color(1.0, 0.0, 1.0));
+  c.render_into(&w, &mut image);
+  assert!(image.pixel_at(5, 5).equals(Tuple::color(0.38066, 0.47583, 0.2855)));
+  assert!(image.pixel_at(0, 0).equals(c.render(&w).pixel_at(0, 0)));
+}
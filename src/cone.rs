@@ -0,0 +1,175 @@
+use crate::tuple::{Tuple, EPSILON};
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::cylinder::check_cap;
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+#[cfg(test)]
+use std::f64::consts::SQRT_2;
+
+// double napped cone around the y axis, its radius at any y is |y|
+#[derive(Clone)]
+pub struct Cone{ data: ShapeData, pub minimum: f64, pub maximum: f64, pub closed: bool }
+impl Cone {
+  pub fn new() -> Self {
+    Cone{data: ShapeData::new(), minimum: f64::NEG_INFINITY, maximum: f64::INFINITY, closed: false}
+  }
+  fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+    if !self.closed || ray.direction.y.abs() < EPSILON {
+      return;
+    }
+    let t = (self.minimum - ray.origin.y) / ray.direction.y;
+    if check_cap(ray, t, self.minimum.abs()) {
+      xs.push(Intersection::new(t, self));
+    }
+    let t = (self.maximum - ray.origin.y) / ray.direction.y;
+    if check_cap(ray, t, self.maximum.abs()) {
+      xs.push(Intersection::new(t, self));
+    }
+  }
+  fn push_if_in_bounds<'a>(&'a self, ray: &Ray, t: f64, xs: &mut Vec<Intersection<'a>>) {
+    let y = ray.origin.y + t * ray.direction.y;
+    if self.minimum < y && y < self.maximum {
+      xs.push(Intersection::new(t, self));
+    }
+  }
+}
+impl Default for Cone {
+  fn default() -> Self {
+    Cone::new()
+  }
+}
+impl Shape for Cone {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let mut xs = vec![];
+    let (o, d) = (ray.origin, ray.direction);
+    let a = d.x * d.x - d.y * d.y + d.z * d.z;
+    let b = 2.0 * o.x * d.x - 2.0 * o.y * d.y + 2.0 * o.z * d.z;
+    let c = o.x * o.x - o.y * o.y + o.z * o.z;
+    if a.abs() < EPSILON {
+      // parallel to one half of the cone, so the body is crossed at most once
+      if b.abs() >= EPSILON {
+        self.push_if_in_bounds(ray, -c / (2.0 * b), &mut xs);
+      }
+    }
+    else {
+      let disc = b * b - 4.0 * a * c;
+      if disc < 0.0 {
+        return xs;
+      }
+      let mut t0 = (-b - disc.sqrt()) / (2.0 * a);
+      let mut t1 = (-b + disc.sqrt()) / (2.0 * a);
+      if t0 > t1 {
+        std::mem::swap(&mut t0, &mut t1);
+      }
+      self.push_if_in_bounds(ray, t0, &mut xs);
+      self.push_if_in_bounds(ray, t1, &mut xs);
+    }
+    self.intersect_caps(ray, &mut xs);
+    xs
+  }
+  fn local_normal_at(&self, point: Tuple) -> Tuple {
+    let dist = point.x * point.x + point.z * point.z;
+    if dist < point.y * point.y && point.y >= self.maximum - EPSILON {
+      Tuple::vector(0.0, 1.0, 0.0)
+    }
+    else if dist < point.y * point.y && point.y <= self.minimum + EPSILON {
+      Tuple::vector(0.0, -1.0, 0.0)
+    }
+    else {
+      let mut y = dist.sqrt();
+      if point.y > 0.0 {
+        y = -y;
+      }
+      Tuple::vector(point.x, y, point.z)
+    }
+  }
+  fn bounds(&self) -> BoundingBox {
+    let limit = self.minimum.abs().max(self.maximum.abs());
+    BoundingBox::new(Tuple::point(-limit, self.minimum, -limit), Tuple::point(limit, self.maximum, limit))
+  }
+}
+
+#[test]
+fn intersecting_a_cone_with_a_ray() {
+  let shape = Cone::new();
+  let cases = [
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 5.0, 5.0),
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0), 8.66025, 8.66025),
+    (Tuple::point(1.0, 1.0, -5.0), Tuple::vector(-0.5, -1.0, 1.0), 4.55006, 49.44994)
+  ];
+  for (origin, direction, t0, t1) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    let xs = shape.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0].t - t0).abs() < 0.0001);
+    assert!((xs[1].t - t1).abs() < 0.0001);
+  }
+}
+#[test]
+fn intersecting_a_cone_with_a_ray_parallel_to_one_of_its_halves() {
+  let shape = Cone::new();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -1.0), Tuple::vector(0.0, 1.0, 1.0).normalize());
+  let xs = shape.local_intersect(&r);
+  assert_eq!(xs.len(), 1);
+  assert!((xs[0].t - 0.35355).abs() < EPSILON);
+}
+#[test]
+fn intersecting_a_cones_end_caps() {
+  let mut shape = Cone::new();
+  shape.minimum = -0.5;
+  shape.maximum = 0.5;
+  shape.closed = true;
+  let cases = [
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0), 0),
+    (Tuple::point(0.0, 0.0, -0.25), Tuple::vector(0.0, 1.0, 1.0), 2),
+    (Tuple::point(0.0, 0.0, -0.25), Tuple::vector(0.0, 1.0, 0.0), 4)
+  ];
+  for (origin, direction, count) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    assert_eq!(shape.local_intersect(&r).len(), count);
+  }
+}
+#[test]
+fn computing_the_normal_vector_on_a_cone() {
+  let shape = Cone::new();
+  let cases = [
+    (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 0.0)),
+    (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, -SQRT_2, 1.0)),
+    (Tuple::point(-1.0, -1.0, 0.0), Tuple::vector(-1.0, 1.0, 0.0))
+  ];
+  for (point, normal) in cases {
+    assert!(shape.local_normal_at(point).equals(normal));
+  }
+}
+#[test]
+fn the_normal_vector_on_a_cones_end_caps() {
+  let mut shape = Cone::new();
+  shape.minimum = -1.0;
+  shape.maximum = 1.0;
+  shape.closed = true;
+  assert!(shape.local_normal_at(Tuple::point(0.5, 1.0, 0.0)).equals(Tuple::vector(0.0, 1.0, 0.0)));
+  assert!(shape.local_normal_at(Tuple::point(0.0, -1.0, 0.5)).equals(Tuple::vector(0.0, -1.0, 0.0)));
+}
+#[test]
+fn an_unbounded_cone_has_a_bounding_box() {
+  let shape = Cone::new();
+  let b = shape.bounds();
+  assert!(b.min.x == f64::NEG_INFINITY && b.min.y == f64::NEG_INFINITY && b.min.z == f64::NEG_INFINITY);
+  assert!(b.max.x == f64::INFINITY && b.max.y == f64::INFINITY && b.max.z == f64::INFINITY);
+}
+#[test]
+fn a_bounded_cone_has_a_bounding_box() {
+  let mut shape = Cone::new();
+  shape.minimum = -5.0;
+  shape.maximum = 3.0;
+  let b = shape.bounds();
+  assert!(b.min.equals(Tuple::point(-5.0, -5.0, -5.0)));
+  assert!(b.max.equals(Tuple::point(5.0, 3.0, 5.0)));
+}
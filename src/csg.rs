@@ -0,0 +1,271 @@
+use crate::tuple::Tuple;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::cube::Cube;
+#[cfg(test)]
+use crate::group::Group;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Operation {
+  Union,
+  Intersection,
+  Difference
+}
+
+// should a hit on the left (lhit) or right shape survive, given whether
+// the ray is currently inside the left (inl) and inside the right (inr)
+pub fn intersection_allowed(op: Operation, lhit: bool, inl: bool, inr: bool) -> bool {
+  match op {
+    Operation::Union => (lhit && !inr) || (!lhit && !inl),
+    Operation::Intersection => (lhit && inr) || (!lhit && inl),
+    Operation::Difference => (lhit && !inr) || (!lhit && inl)
+  }
+}
+
+// constructive solid geometry, children[0] is the left operand and children[1] the right.
+// bounds caches the merged extents of both so every ray doesn't have to recompute them
+pub struct Csg{ data: ShapeData, pub operation: Operation, children: Vec<Box<dyn Shape>>, bounds: BoundingBox }
+impl Csg {
+  pub fn new(operation: Operation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+    let mut csg = Csg{data: ShapeData::new(), operation, children: vec![left, right], bounds: BoundingBox::empty()};
+    csg.propagate_parents();
+    csg
+  }
+  fn refresh_bounds(&mut self) {
+    let mut b = BoundingBox::empty();
+    for child in self.children.iter() {
+      b.merge(&child.parent_space_bounds());
+    }
+    self.bounds = b;
+  }
+  pub fn left(&self) -> &dyn Shape {
+    self.children[0].as_ref()
+  }
+  pub fn right(&self) -> &dyn Shape {
+    self.children[1].as_ref()
+  }
+  // xs must be sorted by t
+  pub fn filter_intersections<'a>(&self, xs: Vec<Intersection<'a>>) -> Vec<Intersection<'a>> {
+    let mut inl = false;
+    let mut inr = false;
+    let mut result = vec![];
+    for i in xs {
+      let lhit = self.left().includes(i.object);
+      if intersection_allowed(self.operation, lhit, inl, inr) {
+        result.push(i);
+      }
+      if lhit {
+        inl = !inl;
+      }
+      else {
+        inr = !inr;
+      }
+    }
+    result
+  }
+}
+impl Shape for Csg {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn propagate_parents(&mut self) {
+    let mut parents = self.parents().to_vec();
+    parents.push(self.inverse().clone());
+    for child in self.children.iter_mut() {
+      child.set_parents(parents.clone());
+    }
+    self.refresh_bounds();
+  }
+  fn children(&self) -> &[Box<dyn Shape>] {
+    &self.children
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let mut xs = vec![];
+    if !self.bounds.intersects(ray) {
+      return xs;
+    }
+    xs.extend(self.left().intersect(ray));
+    xs.extend(self.right().intersect(ray));
+    xs.sort_by(|a, b| a.t.total_cmp(&b.t));
+    self.filter_intersections(xs)
+  }
+  fn local_normal_at(&self, _point: Tuple) -> Tuple {
+    panic!("csg shapes have no surface of their own, normals come from their children");
+  }
+  fn bounds(&self) -> BoundingBox {
+    self.bounds
+  }
+  fn divide(&mut self, threshold: usize) {
+    for child in self.children.iter_mut() {
+      child.divide(threshold);
+    }
+    self.refresh_bounds();
+  }
+  fn build_bvh(&mut self) {
+    for child in self.children.iter_mut() {
+      child.build_bvh();
+    }
+  }
+}
+
+#[test]
+fn csg_is_created_with_an_operation_and_two_shapes() {
+  let c = Csg::new(Operation::Union, Box::new(Sphere::new()), Box::new(Cube::new()));
+  assert_eq!(c.operation, Operation::Union);
+  assert_eq!(c.left().parents().len(), 1);
+  assert_eq!(c.right().parents().len(), 1);
+}
+#[test]
+fn evaluating_the_rule_for_a_csg_operation() {
+  use Operation::*;
+  let cases = [
+    (Union, true, true, true, false),
+    (Union, true, true, false, true),
+    (Union, true, false, true, false),
+    (Union, true, false, false, true),
+    (Union, false, true, true, false),
+    (Union, false, true, false, false),
+    (Union, false, false, true, true),
+    (Union, false, false, false, true),
+    (Intersection, true, true, true, true),
+    (Intersection, true, true, false, false),
+    (Intersection, true, false, true, true),
+    (Intersection, true, false, false, false),
+    (Intersection, false, true, true, true),
+    (Intersection, false, true, false, true),
+    (Intersection, false, false, true, false),
+    (Intersection, false, false, false, false),
+    (Difference, true, true, true, false),
+    (Difference, true, true, false, true),
+    (Difference, true, false, true, false),
+    (Difference, true, false, false, true),
+    (Difference, false, true, true, true),
+    (Difference, false, true, false, true),
+    (Difference, false, false, true, false),
+    (Difference, false, false, false, false)
+  ];
+  for (op, lhit, inl, inr, result) in cases {
+    assert_eq!(intersection_allowed(op, lhit, inl, inr), result);
+  }
+}
+#[test]
+fn filtering_a_list_of_intersections() {
+  let cases = [
+    (Operation::Union, 0, 3),
+    (Operation::Intersection, 1, 2),
+    (Operation::Difference, 0, 1)
+  ];
+  for (op, x0, x1) in cases {
+    let c = Csg::new(op, Box::new(Sphere::new()), Box::new(Cube::new()));
+    let (s1, s2) = (c.left(), c.right());
+    let xs = vec![
+      Intersection::new(1.0, s1),
+      Intersection::new(2.0, s2),
+      Intersection::new(3.0, s1),
+      Intersection::new(4.0, s2)
+    ];
+    let result = c.filter_intersections(xs.clone());
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].t, xs[x0].t);
+    assert_eq!(result[1].t, xs[x1].t);
+  }
+}
+#[test]
+fn a_ray_misses_a_csg_object() {
+  let c = Csg::new(Operation::Union, Box::new(Sphere::new()), Box::new(Cube::new()));
+  let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert!(c.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_hits_a_csg_object() {
+  let s1 = Sphere::new();
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(0.0, 0.0, 0.5));
+  let c = Csg::new(Operation::Union, Box::new(s1), Box::new(s2));
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = c.local_intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 4.0);
+  assert!(std::ptr::addr_eq(xs[0].object, c.left()));
+  assert_eq!(xs[1].t, 6.5);
+  assert!(std::ptr::addr_eq(xs[1].object, c.right()));
+}
+#[test]
+fn filtering_sees_through_groups_inside_a_csg() {
+  let mut left = Group::new();
+  left.add_child(Box::new(Sphere::new()));
+  let mut right = Sphere::new();
+  right.set_transform(Matrix::translation(0.0, 0.0, 0.5));
+  let c = Csg::new(Operation::Difference, Box::new(left), Box::new(right));
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = c.local_intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 4.0);
+  assert_eq!(xs[1].t, 4.5);
+  assert!(c.left().includes(xs[0].object));
+  assert!(c.right().includes(xs[1].object));
+}
+#[test]
+fn a_csg_includes_shapes_nested_inside_it() {
+  let c = Csg::new(Operation::Union, Box::new(Sphere::new()), Box::new(Cube::new()));
+  let other = Sphere::new();
+  assert!(c.includes(c.left()));
+  assert!(c.includes(c.right()));
+  assert!(c.includes(&c));
+  assert!(!c.includes(&other));
+}
+#[test]
+fn a_csg_shape_has_a_bounding_box_that_contains_its_children() {
+  let left = Sphere::new();
+  let mut right = Sphere::new();
+  right.set_transform(Matrix::translation(2.0, 3.0, 4.0));
+  let shape = Csg::new(Operation::Difference, Box::new(left), Box::new(right));
+  let b = shape.bounds();
+  assert!(b.min.equals(Tuple::point(-1.0, -1.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(3.0, 4.0, 5.0)));
+}
+#[test]
+fn subdividing_a_csg_shape_subdivides_its_children() {
+  let mut s1 = Sphere::new();
+  s1.set_transform(Matrix::translation(-1.5, 0.0, 0.0));
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(1.5, 0.0, 0.0));
+  let mut left = Group::new();
+  left.add_child(Box::new(s1));
+  left.add_child(Box::new(s2));
+  let mut s3 = Sphere::new();
+  s3.set_transform(Matrix::translation(0.0, 0.0, -1.5));
+  let mut s4 = Sphere::new();
+  s4.set_transform(Matrix::translation(0.0, 0.0, 1.5));
+  let mut right = Group::new();
+  right.add_child(Box::new(s3));
+  right.add_child(Box::new(s4));
+  let mut shape = Csg::new(Operation::Difference, Box::new(left), Box::new(right));
+  shape.divide(1);
+  assert_eq!(shape.left().children().len(), 2);
+  assert_eq!(shape.left().children()[0].children().len(), 1);
+  assert_eq!(shape.right().children()[1].children().len(), 1);
+}
+#[test]
+fn a_csg_shape_keeps_its_bounding_box_when_transformed() {
+  let left = Sphere::new();
+  let mut right = Sphere::new();
+  right.set_transform(Matrix::translation(2.0, 3.0, 4.0));
+  let mut shape = Csg::new(Operation::Union, Box::new(left), Box::new(right));
+  shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let b = shape.bounds();
+  assert!(b.min.equals(Tuple::point(-1.0, -1.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(3.0, 4.0, 5.0)));
+  let p = shape.parent_space_bounds();
+  assert!(p.max.equals(Tuple::point(6.0, 8.0, 10.0)));
+}
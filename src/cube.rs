@@ -0,0 +1,117 @@
+use crate::tuple::Tuple;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::{BoundingBox, check_axis};
+
+// axis aligned cube spanning -1 to 1 on every axis
+#[derive(Clone)]
+pub struct Cube{ data: ShapeData }
+impl Cube {
+  pub fn new() -> Self {
+    Cube{data: ShapeData::new()}
+  }
+}
+impl Default for Cube {
+  fn default() -> Self {
+    Cube::new()
+  }
+}
+
+impl Shape for Cube {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, -1.0, 1.0);
+    let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, -1.0, 1.0);
+    let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, -1.0, 1.0);
+    let tmin = xtmin.max(ytmin).max(ztmin);
+    let tmax = xtmax.min(ytmax).min(ztmax);
+    if tmin > tmax {
+      return vec![];
+    }
+    vec![Intersection::new(tmin, self), Intersection::new(tmax, self)]
+  }
+  fn local_normal_at(&self, point: Tuple) -> Tuple {
+    // the face the point is on is the axis with the largest component
+    let maxc = point.x.abs().max(point.y.abs()).max(point.z.abs());
+    if maxc == point.x.abs() {
+      Tuple::vector(point.x, 0.0, 0.0)
+    }
+    else if maxc == point.y.abs() {
+      Tuple::vector(0.0, point.y, 0.0)
+    }
+    else {
+      Tuple::vector(0.0, 0.0, point.z)
+    }
+  }
+  fn bounds(&self) -> BoundingBox {
+    BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+  }
+}
+
+#[test]
+fn a_ray_intersects_a_cube() {
+  let c = Cube::new();
+  let cases = [
+    (Tuple::point(5.0, 0.5, 0.0), Tuple::vector(-1.0, 0.0, 0.0), 4.0, 6.0),
+    (Tuple::point(-5.0, 0.5, 0.0), Tuple::vector(1.0, 0.0, 0.0), 4.0, 6.0),
+    (Tuple::point(0.5, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), 4.0, 6.0),
+    (Tuple::point(0.5, -5.0, 0.0), Tuple::vector(0.0, 1.0, 0.0), 4.0, 6.0),
+    (Tuple::point(0.5, 0.0, 5.0), Tuple::vector(0.0, 0.0, -1.0), 4.0, 6.0),
+    (Tuple::point(0.5, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 4.0, 6.0),
+    (Tuple::point(0.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0), -1.0, 1.0)
+  ];
+  for (origin, direction, t1, t2) in cases {
+    let r = Ray::new(origin, direction);
+    let xs = c.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t, t1);
+    assert_eq!(xs[1].t, t2);
+  }
+}
+#[test]
+fn a_ray_misses_a_cube() {
+  let c = Cube::new();
+  let cases = [
+    (Tuple::point(-2.0, 0.0, 0.0), Tuple::vector(0.2673, 0.5345, 0.8018)),
+    (Tuple::point(0.0, -2.0, 0.0), Tuple::vector(0.8018, 0.2673, 0.5345)),
+    (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.5345, 0.8018, 0.2673)),
+    (Tuple::point(2.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, -1.0)),
+    (Tuple::point(0.0, 2.0, 2.0), Tuple::vector(0.0, -1.0, 0.0)),
+    (Tuple::point(2.0, 2.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0))
+  ];
+  for (origin, direction) in cases {
+    let r = Ray::new(origin, direction);
+    let xs = c.local_intersect(&r);
+    assert!(xs.is_empty());
+  }
+}
+#[test]
+fn the_normal_on_the_surface_of_a_cube() {
+  let c = Cube::new();
+  let cases = [
+    (Tuple::point(1.0, 0.5, -0.8), Tuple::vector(1.0, 0.0, 0.0)),
+    (Tuple::point(-1.0, -0.2, 0.9), Tuple::vector(-1.0, 0.0, 0.0)),
+    (Tuple::point(-0.4, 1.0, -0.1), Tuple::vector(0.0, 1.0, 0.0)),
+    (Tuple::point(0.3, -1.0, -0.7), Tuple::vector(0.0, -1.0, 0.0)),
+    (Tuple::point(-0.6, 0.3, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+    (Tuple::point(0.4, 0.4, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+    (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, 0.0, 0.0)),
+    (Tuple::point(-1.0, -1.0, -1.0), Tuple::vector(-1.0, 0.0, 0.0))
+  ];
+  for (point, normal) in cases {
+    assert!(c.local_normal_at(point).equals(normal));
+  }
+}
+#[test]
+fn a_cube_has_a_bounding_box() {
+  let c = Cube::new();
+  let b = c.bounds();
+  assert!(b.min.equals(Tuple::point(-1.0, -1.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(1.0, 1.0, 1.0)));
+}
@@ -0,0 +1,212 @@
+use crate::tuple::{Tuple, EPSILON};
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+
+// radius 1 cylinder around the y axis, truncated to minimum..maximum and capped when closed
+#[derive(Clone)]
+pub struct Cylinder{ data: ShapeData, pub minimum: f64, pub maximum: f64, pub closed: bool }
+impl Cylinder {
+  pub fn new() -> Self {
+    Cylinder{data: ShapeData::new(), minimum: f64::NEG_INFINITY, maximum: f64::INFINITY, closed: false}
+  }
+  fn intersect_caps<'a>(&'a self, ray: &Ray, xs: &mut Vec<Intersection<'a>>) {
+    // caps only matter when closed, and can only be hit by a ray with some y slope
+    if !self.closed || ray.direction.y.abs() < EPSILON {
+      return;
+    }
+    let t = (self.minimum - ray.origin.y) / ray.direction.y;
+    if check_cap(ray, t, 1.0) {
+      xs.push(Intersection::new(t, self));
+    }
+    let t = (self.maximum - ray.origin.y) / ray.direction.y;
+    if check_cap(ray, t, 1.0) {
+      xs.push(Intersection::new(t, self));
+    }
+  }
+}
+// is the point the ray reaches at t within radius of the y axis, shared with cone caps
+pub fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+  let x = ray.origin.x + t * ray.direction.x;
+  let z = ray.origin.z + t * ray.direction.z;
+  (x * x + z * z) <= radius * radius
+}
+
+impl Default for Cylinder {
+  fn default() -> Self {
+    Cylinder::new()
+  }
+}
+impl Shape for Cylinder {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let mut xs = vec![];
+    let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
+    // a ray parallel to the y axis can only hit the caps
+    if a.abs() >= EPSILON {
+      let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+      let c = ray.origin.x * ray.origin.x + ray.origin.z * ray.origin.z - 1.0;
+      let disc = b * b - 4.0 * a * c;
+      if disc < 0.0 {
+        return xs;
+      }
+      let mut t0 = (-b - disc.sqrt()) / (2.0 * a);
+      let mut t1 = (-b + disc.sqrt()) / (2.0 * a);
+      if t0 > t1 {
+        std::mem::swap(&mut t0, &mut t1);
+      }
+      let y0 = ray.origin.y + t0 * ray.direction.y;
+      if self.minimum < y0 && y0 < self.maximum {
+        xs.push(Intersection::new(t0, self));
+      }
+      let y1 = ray.origin.y + t1 * ray.direction.y;
+      if self.minimum < y1 && y1 < self.maximum {
+        xs.push(Intersection::new(t1, self));
+      }
+    }
+    self.intersect_caps(ray, &mut xs);
+    xs
+  }
+  fn local_normal_at(&self, point: Tuple) -> Tuple {
+    let dist = point.x * point.x + point.z * point.z;
+    if dist < 1.0 && point.y >= self.maximum - EPSILON {
+      Tuple::vector(0.0, 1.0, 0.0)
+    }
+    else if dist < 1.0 && point.y <= self.minimum + EPSILON {
+      Tuple::vector(0.0, -1.0, 0.0)
+    }
+    else {
+      Tuple::vector(point.x, 0.0, point.z)
+    }
+  }
+  fn bounds(&self) -> BoundingBox {
+    BoundingBox::new(Tuple::point(-1.0, self.minimum, -1.0), Tuple::point(1.0, self.maximum, 1.0))
+  }
+}
+
+#[test]
+fn a_ray_misses_a_cylinder() {
+  let cyl = Cylinder::new();
+  let cases = [
+    (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+    (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0))
+  ];
+  for (origin, direction) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    let xs = cyl.local_intersect(&r);
+    assert!(xs.is_empty());
+  }
+}
+#[test]
+fn a_ray_strikes_a_cylinder() {
+  let cyl = Cylinder::new();
+  let cases = [
+    (Tuple::point(1.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 5.0, 5.0),
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 4.0, 6.0),
+    (Tuple::point(0.5, 0.0, -5.0), Tuple::vector(0.1, 1.0, 1.0), 6.80798, 7.08872)
+  ];
+  for (origin, direction, t0, t1) in cases {
+    let r = Ray::new(origin, direction.normalize());
+    let xs = cyl.local_intersect(&r);
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0].t - t0).abs() < EPSILON);
+    assert!((xs[1].t - t1).abs() < EPSILON);
+  }
+}
+#[test]
+fn normal_vector_on_a_cylinder() {
+  let cyl = Cylinder::new();
+  let cases = [
+    (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+    (Tuple::point(0.0, 5.0, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+    (Tuple::point(0.0, -2.0, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+    (Tuple::point(-1.0, 1.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0))
+  ];
+  for (point, normal) in cases {
+    assert!(cyl.local_normal_at(point).equals(normal));
+  }
+}
+#[test]
+fn the_default_minimum_and_maximum_for_a_cylinder() {
+  let cyl = Cylinder::new();
+  assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+  assert_eq!(cyl.maximum, f64::INFINITY);
+  assert!(!cyl.closed);
+}
+#[test]
+fn intersecting_a_constrained_cylinder() {
+  let mut cyl = Cylinder::new();
+  cyl.minimum = 1.0;
+  cyl.maximum = 2.0;
+  let cases = [
+    (Tuple::point(0.0, 1.5, 0.0), Tuple::vector(0.1, 1.0, 0.0), 0),
+    (Tuple::point(0.0, 3.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+    (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+    (Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+    (Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0), 0),
+    (Tuple::point(0.0, 1.5, -2.0), Tuple::vector(0.0, 0.0, 1.0), 2)
+  ];
+  for (point, direction, count) in cases {
+    let r = Ray::new(point, direction.normalize());
+    assert_eq!(cyl.local_intersect(&r).len(), count);
+  }
+}
+#[test]
+fn intersecting_the_caps_of_a_closed_cylinder() {
+  let mut cyl = Cylinder::new();
+  cyl.minimum = 1.0;
+  cyl.maximum = 2.0;
+  cyl.closed = true;
+  let cases = [
+    (Tuple::point(0.0, 3.0, 0.0), Tuple::vector(0.0, -1.0, 0.0), 2),
+    (Tuple::point(0.0, 3.0, -2.0), Tuple::vector(0.0, -1.0, 2.0), 2),
+    (Tuple::point(0.0, 4.0, -2.0), Tuple::vector(0.0, -1.0, 1.0), 2),
+    (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.0, 1.0, 2.0), 2),
+    (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 1.0), 2)
+  ];
+  for (point, direction, count) in cases {
+    let r = Ray::new(point, direction.normalize());
+    assert_eq!(cyl.local_intersect(&r).len(), count);
+  }
+}
+#[test]
+fn the_normal_vector_on_a_cylinders_end_caps() {
+  let mut cyl = Cylinder::new();
+  cyl.minimum = 1.0;
+  cyl.maximum = 2.0;
+  cyl.closed = true;
+  let cases = [
+    (Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+    (Tuple::point(0.5, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+    (Tuple::point(0.0, 1.0, 0.5), Tuple::vector(0.0, -1.0, 0.0)),
+    (Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+    (Tuple::point(0.5, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+    (Tuple::point(0.0, 2.0, 0.5), Tuple::vector(0.0, 1.0, 0.0))
+  ];
+  for (point, normal) in cases {
+    assert!(cyl.local_normal_at(point).equals(normal));
+  }
+}
+#[test]
+fn an_unbounded_cylinder_has_a_bounding_box() {
+  let cyl = Cylinder::new();
+  let b = cyl.bounds();
+  assert!(b.min.x == -1.0 && b.min.y == f64::NEG_INFINITY && b.min.z == -1.0);
+  assert!(b.max.x == 1.0 && b.max.y == f64::INFINITY && b.max.z == 1.0);
+}
+#[test]
+fn a_bounded_cylinder_has_a_bounding_box() {
+  let mut cyl = Cylinder::new();
+  cyl.minimum = -5.0;
+  cyl.maximum = 3.0;
+  let b = cyl.bounds();
+  assert!(b.min.equals(Tuple::point(-1.0, -5.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(1.0, 3.0, 1.0)));
+}
@@ -0,0 +1,400 @@
+use crate::tuple::Tuple;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+use crate::bvh::Bvh;
+#[cfg(test)]
+use std::f64::consts::PI;
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::cylinder::Cylinder;
+#[cfg(test)]
+use crate::shape::TestShape;
+#[cfg(test)]
+use crate::triangle::Triangle;
+
+type Children = Vec<Box<dyn Shape>>;
+
+// a collection of shapes that are transformed and intersected as one,
+// bounds is kept up to date as children are added so misses can skip them all.
+// once built, bvh indexes children and is dropped again whenever they change
+pub struct Group{ data: ShapeData, children: Children, bounds: BoundingBox, bvh: Option<Bvh> }
+impl Group {
+  pub fn new() -> Self {
+    Group{data: ShapeData::new(), children: vec![], bounds: BoundingBox::empty(), bvh: None}
+  }
+  pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+    child.set_parents(self.child_parents());
+    self.bounds.merge(&child.parent_space_bounds());
+    self.children.push(child);
+    self.bvh = None;
+  }
+  // takes out the children that fit entirely in either half of the bounds,
+  // anything straddling the split stays where it is
+  pub fn partition_children(&mut self) -> (Children, Children) {
+    let (left_bounds, right_bounds) = self.bounds.split();
+    let mut left = vec![];
+    let mut right = vec![];
+    let mut remaining = vec![];
+    for child in self.children.drain(..) {
+      let child_bounds = child.parent_space_bounds();
+      if left_bounds.contains_box(&child_bounds) {
+        left.push(child);
+      }
+      else if right_bounds.contains_box(&child_bounds) {
+        right.push(child);
+      }
+      else {
+        remaining.push(child);
+      }
+    }
+    self.children = remaining;
+    self.bvh = None;
+    (left, right)
+  }
+  pub fn make_subgroup(&mut self, children: Children) {
+    let mut subgroup = Group::new();
+    for child in children {
+      subgroup.add_child(child);
+    }
+    self.add_child(Box::new(subgroup));
+  }
+  pub fn is_empty(&self) -> bool {
+    self.children.is_empty()
+  }
+  // the chain a child sees: everything above this group plus this group itself
+  fn child_parents(&self) -> Vec<Matrix> {
+    let mut parents = self.parents().to_vec();
+    parents.push(self.inverse().clone());
+    parents
+  }
+}
+impl Default for Group {
+  fn default() -> Self {
+    Group::new()
+  }
+}
+impl Shape for Group {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn propagate_parents(&mut self) {
+    let parents = self.child_parents();
+    for child in self.children.iter_mut() {
+      child.set_parents(parents.clone());
+    }
+  }
+  fn children(&self) -> &[Box<dyn Shape>] {
+    &self.children
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let mut xs = vec![];
+    // refraction and csg filtering need every intersection, not just the nearest,
+    // so the walk can't stop at the first leaf with a hit
+    if let Some(bvh) = &self.bvh {
+      bvh.traverse(ray, |range| {
+        for child in self.children[range].iter() {
+          xs.extend(child.intersect(ray));
+        }
+      });
+      return xs;
+    }
+    if !self.bounds.intersects(ray) {
+      return xs;
+    }
+    for child in self.children.iter() {
+      xs.extend(child.intersect(ray));
+    }
+    xs
+  }
+  fn local_normal_at(&self, _point: Tuple) -> Tuple {
+    panic!("groups have no surface of their own, normals come from their children");
+  }
+  fn bounds(&self) -> BoundingBox {
+    self.bounds
+  }
+  fn divide(&mut self, threshold: usize) {
+    if threshold <= self.children.len() {
+      let count = self.children.len();
+      let (left, right) = self.partition_children();
+      // when everything lands on one side (children with no extent along the split, say)
+      // another level would look exactly like this one, so stop here
+      if left.len() == count || right.len() == count {
+        self.children = if left.is_empty() { right } else { left };
+      }
+      else {
+        if !left.is_empty() {
+          self.make_subgroup(left);
+        }
+        if !right.is_empty() {
+          self.make_subgroup(right);
+        }
+      }
+    }
+    for child in self.children.iter_mut() {
+      child.divide(threshold);
+    }
+  }
+  fn build_bvh(&mut self) {
+    for child in self.children.iter_mut() {
+      child.build_bvh();
+    }
+    let child_bounds: Vec<BoundingBox> = self.children.iter().map(|c| c.parent_space_bounds()).collect();
+    let (bvh, order) = Bvh::build(&child_bounds);
+    // store the children in leaf order so each leaf covers a contiguous slice
+    let mut slots: Vec<Option<Box<dyn Shape>>> = self.children.drain(..).map(Some).collect();
+    self.children = order.iter().map(|&i| slots[i].take().unwrap()).collect();
+    self.bvh = Some(bvh);
+  }
+}
+
+#[test]
+fn creating_a_new_group() {
+  let g = Group::new();
+  assert!(g.transform().equals(Matrix::identity()));
+  assert!(g.is_empty());
+}
+#[test]
+fn adding_a_child_to_a_group() {
+  let mut g = Group::new();
+  g.set_transform(Matrix::translation(1.0, 2.0, 3.0));
+  g.add_child(Box::new(Sphere::new()));
+  assert!(!g.is_empty());
+  assert_eq!(g.children()[0].parents().len(), 1);
+  assert!(g.children()[0].parents()[0].equals(g.inverse().clone()));
+}
+#[test]
+fn intersecting_a_ray_with_an_empty_group() {
+  let g = Group::new();
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert!(g.local_intersect(&r).is_empty());
+}
+#[test]
+fn intersecting_a_ray_with_a_nonempty_group() {
+  let mut g = Group::new();
+  let s1 = Sphere::new();
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(0.0, 0.0, -3.0));
+  let mut s3 = Sphere::new();
+  s3.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  g.add_child(Box::new(s1));
+  g.add_child(Box::new(s2));
+  g.add_child(Box::new(s3));
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = g.intersect(&r);
+  let s1 = g.children()[0].as_ref();
+  let s2 = g.children()[1].as_ref();
+  assert_eq!(xs.len(), 4);
+  assert!(std::ptr::addr_eq(xs[0].object, s2));
+  assert!(std::ptr::addr_eq(xs[1].object, s2));
+  assert!(std::ptr::addr_eq(xs[2].object, s1));
+  assert!(std::ptr::addr_eq(xs[3].object, s1));
+}
+#[test]
+fn intersecting_a_transformed_group() {
+  let mut g = Group::new();
+  g.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  g.add_child(Box::new(s));
+  let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = g.intersect(&r);
+  assert_eq!(xs.len(), 2);
+}
+
+// g1 rotated, holding g2 scaled, holding a translated sphere
+#[cfg(test)]
+fn nested_groups() -> Group {
+  let mut g1 = Group::new();
+  g1.set_transform(Matrix::rotation_y(PI/2.0));
+  let mut g2 = Group::new();
+  g2.set_transform(Matrix::scaling(1.0, 2.0, 3.0));
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  g2.add_child(Box::new(s));
+  g1.add_child(Box::new(g2));
+  g1
+}
+#[test]
+fn converting_a_point_from_world_to_object_space() {
+  let mut g1 = Group::new();
+  g1.set_transform(Matrix::rotation_y(PI/2.0));
+  let mut g2 = Group::new();
+  g2.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  g2.add_child(Box::new(s));
+  g1.add_child(Box::new(g2));
+  let s = g1.children()[0].children()[0].as_ref();
+  let p = s.world_to_object(Tuple::point(-2.0, 0.0, -10.0));
+  assert!(p.equals(Tuple::point(0.0, 0.0, -1.0)));
+}
+#[test]
+fn converting_a_normal_from_object_to_world_space() {
+  let g1 = nested_groups();
+  let s = g1.children()[0].children()[0].as_ref();
+  let v = f64::sqrt(3.0) / 3.0;
+  let n = s.normal_to_world(Tuple::vector(v, v, v));
+  // the expected values are only given to four places
+  assert!((n - Tuple::vector(0.2857, 0.4286, -0.8571)).magnitude() < 0.0001);
+}
+#[test]
+fn finding_the_normal_on_a_child_object() {
+  let g1 = nested_groups();
+  let s = g1.children()[0].children()[0].as_ref();
+  let n = s.normal_at(Tuple::point(1.7321, 1.1547, -5.5774));
+  // the expected values are only given to four places
+  assert!((n - Tuple::vector(0.2857, 0.4286, -0.8571)).magnitude() < 0.0001);
+}
+#[test]
+fn transforming_a_group_after_adding_children_updates_them() {
+  let mut g1 = Group::new();
+  let mut g2 = Group::new();
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  g2.add_child(Box::new(s));
+  g1.add_child(Box::new(g2));
+  g1.set_transform(Matrix::rotation_y(PI/2.0));
+  let s = g1.children()[0].children()[0].as_ref();
+  assert_eq!(s.parents().len(), 2);
+  let p = s.world_to_object(Tuple::point(0.0, 0.0, -6.0));
+  assert!(p.equals(Tuple::point(1.0, 0.0, 0.0)));
+}
+#[test]
+fn a_group_has_a_bounding_box_that_contains_its_children() {
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(2.0, 5.0, -3.0) * Matrix::scaling(2.0, 2.0, 2.0));
+  let mut c = Cylinder::new();
+  c.minimum = -2.0;
+  c.maximum = 2.0;
+  c.set_transform(Matrix::translation(-4.0, -1.0, 4.0) * Matrix::scaling(0.5, 1.0, 0.5));
+  let mut g = Group::new();
+  g.add_child(Box::new(s));
+  g.add_child(Box::new(c));
+  let b = g.bounds();
+  assert!(b.min.equals(Tuple::point(-4.5, -3.0, -5.0)));
+  assert!(b.max.equals(Tuple::point(4.0, 7.0, 4.5)));
+}
+#[test]
+fn intersecting_ray_and_group_doesnt_test_children_if_box_is_missed() {
+  let child = TestShape::new();
+  let saved_ray = child.saved_ray.clone();
+  let mut g = Group::new();
+  g.add_child(Box::new(child));
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+  g.intersect(&r);
+  assert!(saved_ray.borrow().is_none());
+}
+#[test]
+fn intersecting_ray_and_group_tests_children_if_box_is_hit() {
+  let child = TestShape::new();
+  let saved_ray = child.saved_ray.clone();
+  let mut g = Group::new();
+  g.add_child(Box::new(child));
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  g.intersect(&r);
+  assert!(saved_ray.borrow().is_some());
+}
+#[test]
+fn partitioning_a_groups_children() {
+  let mut s1 = Sphere::new();
+  s1.set_transform(Matrix::translation(-2.0, 0.0, 0.0));
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(2.0, 0.0, 0.0));
+  let s3 = Sphere::new();
+  let mut g = Group::new();
+  g.add_child(Box::new(s1));
+  g.add_child(Box::new(s2));
+  g.add_child(Box::new(s3));
+  let (left, right) = g.partition_children();
+  assert_eq!(g.children().len(), 1);
+  assert!(g.children()[0].transform().equals(Matrix::identity()));
+  assert_eq!(left.len(), 1);
+  assert!(left[0].transform().equals(Matrix::translation(-2.0, 0.0, 0.0)));
+  assert_eq!(right.len(), 1);
+  assert!(right[0].transform().equals(Matrix::translation(2.0, 0.0, 0.0)));
+}
+#[test]
+fn creating_a_subgroup_from_a_list_of_children() {
+  let mut g = Group::new();
+  g.make_subgroup(vec![Box::new(Sphere::new()), Box::new(Sphere::new())]);
+  assert_eq!(g.children().len(), 1);
+  assert_eq!(g.children()[0].children().len(), 2);
+  assert_eq!(g.children()[0].children()[0].parents().len(), 2);
+}
+#[test]
+fn subdividing_a_group_partitions_its_children() {
+  let mut s1 = Sphere::new();
+  s1.set_transform(Matrix::translation(-2.0, -2.0, 0.0));
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(-2.0, 2.0, 0.0));
+  let mut s3 = Sphere::new();
+  s3.set_transform(Matrix::scaling(4.0, 4.0, 4.0));
+  let mut g = Group::new();
+  g.add_child(Box::new(s1));
+  g.add_child(Box::new(s2));
+  g.add_child(Box::new(s3));
+  g.divide(1);
+  assert!(g.children()[0].transform().equals(Matrix::scaling(4.0, 4.0, 4.0)));
+  let subgroup = g.children()[1].as_ref();
+  assert_eq!(subgroup.children().len(), 2);
+  assert_eq!(subgroup.children()[0].children().len(), 1);
+  assert!(subgroup.children()[0].children()[0].transform().equals(Matrix::translation(-2.0, -2.0, 0.0)));
+  assert_eq!(subgroup.children()[1].children().len(), 1);
+  assert!(subgroup.children()[1].children()[0].transform().equals(Matrix::translation(-2.0, 2.0, 0.0)));
+}
+#[test]
+fn subdividing_a_group_with_too_few_children() {
+  let mut s1 = Sphere::new();
+  s1.set_transform(Matrix::translation(-2.0, 0.0, 0.0));
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(2.0, 1.0, 0.0));
+  let mut s3 = Sphere::new();
+  s3.set_transform(Matrix::translation(2.0, -1.0, 0.0));
+  let mut subgroup = Group::new();
+  subgroup.add_child(Box::new(s1));
+  subgroup.add_child(Box::new(s2));
+  subgroup.add_child(Box::new(s3));
+  let s4 = Sphere::new();
+  let mut g = Group::new();
+  g.add_child(Box::new(subgroup));
+  g.add_child(Box::new(s4));
+  g.divide(3);
+  assert_eq!(g.children().len(), 2);
+  let subgroup = g.children()[0].as_ref();
+  assert_eq!(subgroup.children().len(), 2);
+  assert_eq!(subgroup.children()[0].children().len(), 1);
+  assert_eq!(subgroup.children()[1].children().len(), 2);
+}
+#[test]
+fn a_divided_group_still_finds_the_same_hits() {
+  let mut g = Group::new();
+  for i in 0..8 {
+    let mut s = Sphere::new();
+    s.set_transform(Matrix::translation(i as f64 * 3.0, 0.0, 0.0));
+    g.add_child(Box::new(s));
+  }
+  let r = Ray::new(Tuple::point(9.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let before: Vec<f64> = g.intersect(&r).into_iter().map(|i| i.t).collect();
+  g.divide(2);
+  let after: Vec<f64> = g.intersect(&r).into_iter().map(|i| i.t).collect();
+  assert_eq!(before, after);
+  assert_eq!(after, vec![4.0, 6.0]);
+}
+#[test]
+fn subdividing_children_that_share_one_point_stops() {
+  let p = Tuple::point(1.0, 2.0, 3.0);
+  let mut g = Group::new();
+  for _ in 0..4 {
+    g.add_child(Box::new(Triangle::new(p, p, p)));
+  }
+  g.divide(1);
+  assert_eq!(g.children().len(), 4);
+  assert!(g.children().iter().all(|c| c.children().is_empty()));
+}
@@ -0,0 +1,303 @@
+use std::ops;
+use crate::tuple::{Tuple, EPSILON};
+use crate::ray::Ray;
+use crate::shape::Shape;
+#[cfg(test)]
+use crate::sphere::{Sphere, glass_sphere};
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use crate::plane::Plane;
+#[cfg(test)]
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+#[derive(Copy, Clone)]
+pub struct Intersection<'a>{ pub t: f64, pub object: &'a dyn Shape, pub u: f64, pub v: f64 }
+impl<'a> Intersection<'a> {
+  pub fn new(t: f64, object: &'a dyn Shape) -> Self {
+    Intersection{t, object, u: 0.0, v: 0.0}
+  }
+  // u and v locate the hit on a triangle relative to its corners
+  pub fn with_uv(t: f64, object: &'a dyn Shape, u: f64, v: f64) -> Self {
+    Intersection{t, object, u, v}
+  }
+  // precompute the values shading needs at the point of this intersection,
+  // xs is the full list this hit came from so the refractive indices either side can be found
+  pub fn prepare_computations(&self, ray: &Ray, xs: &Intersections<'a>) -> Computations<'a> {
+    let point = ray.position(self.t);
+    let eyev = -ray.direction;
+    let mut normalv = self.object.normal_at_hit(point, self);
+    let inside = normalv.dot(eyev) < 0.0;
+    if inside {
+      normalv = -normalv;
+    }
+    let reflectv = ray.direction.reflect(normalv);
+    // lift the point slightly off the surface so it can't shadow itself,
+    // and sink it slightly below for refracted rays to start from
+    let over_point = point + normalv * EPSILON;
+    let under_point = point - normalv * EPSILON;
+    let (n1, n2) = self.refractive_indices(xs);
+    Computations{t: self.t, object: self.object, point, over_point, under_point, eyev, normalv, reflectv, inside, n1, n2}
+  }
+  // walks the list keeping track of which objects the ray is inside of,
+  // n1 is the index of the one it leaves through this hit and n2 the one it enters
+  fn refractive_indices(&self, xs: &Intersections<'a>) -> (f64, f64) {
+    let mut containers: Vec<&dyn Shape> = vec![];
+    let mut n1 = 1.0;
+    for i in xs.list.iter() {
+      let is_hit = i.t == self.t && std::ptr::addr_eq(i.object, self.object);
+      if is_hit {
+        n1 = containers.last().map_or(1.0, |object| object.material().refractive_index);
+      }
+      match containers.iter().position(|object| std::ptr::addr_eq(*object, i.object)) {
+        Some(index) => {
+          containers.remove(index);
+        }
+        None => containers.push(i.object)
+      }
+      if is_hit {
+        let n2 = containers.last().map_or(1.0, |object| object.material().refractive_index);
+        return (n1, n2);
+      }
+    }
+    (n1, 1.0)
+  }
+}
+
+pub struct Computations<'a>{
+  pub t: f64,
+  pub object: &'a dyn Shape,
+  pub point: Tuple,
+  pub over_point: Tuple,
+  pub under_point: Tuple,
+  pub eyev: Tuple,
+  pub normalv: Tuple,
+  pub reflectv: Tuple,
+  pub inside: bool,
+  pub n1: f64,
+  pub n2: f64
+}
+impl<'a> Computations<'a> {
+  // schlick's approximation of the fresnel effect, the fraction of light reflected rather than refracted
+  pub fn schlick(&self) -> f64 {
+    let mut cos = self.eyev.dot(self.normalv);
+    // total internal reflection can only happen going into a lower index
+    if self.n1 > self.n2 {
+      let n = self.n1 / self.n2;
+      let sin2_t = n * n * (1.0 - cos * cos);
+      if sin2_t > 1.0 {
+        return 1.0;
+      }
+      // when n1 > n2 use cos(theta_t) instead
+      cos = f64::sqrt(1.0 - sin2_t);
+    }
+    let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+  }
+}
+
+// always kept sorted by t so the hit is the first non-negative entry
+pub struct Intersections<'a>{ list: Vec<Intersection<'a>> }
+impl<'a> Intersections<'a> {
+  pub fn new(mut list: Vec<Intersection<'a>>) -> Self {
+    list.sort_by(|a, b| a.t.total_cmp(&b.t));
+    Intersections{list}
+  }
+  pub fn len(&self) -> usize {
+    self.list.len()
+  }
+  pub fn is_empty(&self) -> bool {
+    self.list.is_empty()
+  }
+  pub fn hit(&self) -> Option<&Intersection<'a>> {
+    self.list.iter().find(|i| i.t >= 0.0)
+  }
+  pub fn iter(&self) -> std::slice::Iter<'_, Intersection<'a>> {
+    self.list.iter()
+  }
+}
+
+impl<'a> IntoIterator for Intersections<'a> {
+  type Item = Intersection<'a>;
+  type IntoIter = std::vec::IntoIter<Intersection<'a>>;
+  fn into_iter(self) -> Self::IntoIter {
+    self.list.into_iter()
+  }
+}
+
+impl<'a> ops::Index<usize> for Intersections<'a> {
+  type Output = Intersection<'a>;
+  fn index(&self, index: usize) -> &Self::Output {
+    &self.list[index]
+  }
+}
+
+#[test]
+fn an_intersection_encapsulates_t_and_object() {
+  let s = Sphere::new();
+  let i = Intersection::new(3.5, &s);
+  assert_eq!(i.t, 3.5);
+  assert!(std::ptr::addr_eq(i.object, &s));
+}
+#[test]
+fn aggregating_intersections() {
+  let s = Sphere::new();
+  let i1 = Intersection::new(1.0, &s);
+  let i2 = Intersection::new(2.0, &s);
+  let xs = Intersections::new(vec![i1, i2]);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 1.0);
+  assert_eq!(xs[1].t, 2.0);
+}
+#[test]
+fn the_hit_when_all_intersections_have_positive_t() {
+  let s = Sphere::new();
+  let i1 = Intersection::new(1.0, &s);
+  let i2 = Intersection::new(2.0, &s);
+  let xs = Intersections::new(vec![i2, i1]);
+  assert_eq!(xs.hit().unwrap().t, 1.0);
+}
+#[test]
+fn the_hit_when_some_intersections_have_negative_t() {
+  let s = Sphere::new();
+  let i1 = Intersection::new(-1.0, &s);
+  let i2 = Intersection::new(1.0, &s);
+  let xs = Intersections::new(vec![i2, i1]);
+  assert_eq!(xs.hit().unwrap().t, 1.0);
+}
+#[test]
+fn the_hit_when_all_intersections_have_negative_t() {
+  let s = Sphere::new();
+  let i1 = Intersection::new(-2.0, &s);
+  let i2 = Intersection::new(-1.0, &s);
+  let xs = Intersections::new(vec![i2, i1]);
+  assert!(xs.hit().is_none());
+}
+#[test]
+fn the_hit_is_always_the_lowest_nonnegative_intersection() {
+  let s = Sphere::new();
+  let i1 = Intersection::new(5.0, &s);
+  let i2 = Intersection::new(7.0, &s);
+  let i3 = Intersection::new(-3.0, &s);
+  let i4 = Intersection::new(2.0, &s);
+  let xs = Intersections::new(vec![i1, i2, i3, i4]);
+  assert_eq!(xs.hit().unwrap().t, 2.0);
+}
+#[test]
+fn precomputing_the_state_of_an_intersection() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let shape = Sphere::new();
+  let i = Intersection::new(4.0, &shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  assert_eq!(comps.t, i.t);
+  assert!(std::ptr::addr_eq(comps.object, &shape));
+  assert!(comps.point.equals(Tuple::point(0.0, 0.0, -1.0)));
+  assert!(comps.eyev.equals(Tuple::vector(0.0, 0.0, -1.0)));
+  assert!(comps.normalv.equals(Tuple::vector(0.0, 0.0, -1.0)));
+}
+#[test]
+fn the_hit_when_an_intersection_occurs_on_the_outside() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let shape = Sphere::new();
+  let i = Intersection::new(4.0, &shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  assert!(!comps.inside);
+}
+#[test]
+fn the_hit_when_an_intersection_occurs_on_the_inside() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let shape = Sphere::new();
+  let i = Intersection::new(1.0, &shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  assert!(comps.point.equals(Tuple::point(0.0, 0.0, 1.0)));
+  assert!(comps.eyev.equals(Tuple::vector(0.0, 0.0, -1.0)));
+  assert!(comps.inside);
+  // normal would have been (0, 0, 1), but is inverted
+  assert!(comps.normalv.equals(Tuple::vector(0.0, 0.0, -1.0)));
+}
+#[test]
+fn the_hit_should_offset_the_point() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut shape = Sphere::new();
+  shape.set_transform(Matrix::translation(0.0, 0.0, 1.0));
+  let i = Intersection::new(5.0, &shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  assert!(comps.over_point.z < -EPSILON / 2.0);
+  assert!(comps.point.z > comps.over_point.z);
+}
+#[test]
+fn precomputing_the_reflection_vector() {
+  let shape = Plane::new();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 1.0, -1.0), Tuple::vector(0.0, -v, v));
+  let i = Intersection::new(SQRT_2, &shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  assert!(comps.reflectv.equals(Tuple::vector(0.0, v, v)));
+}
+#[test]
+fn finding_n1_and_n2_at_various_intersections() {
+  let mut a = glass_sphere();
+  a.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let mut m = a.material().clone();
+  m.refractive_index = 1.5;
+  a.set_material(m.clone());
+  let mut b = glass_sphere();
+  b.set_transform(Matrix::translation(0.0, 0.0, -0.25));
+  m.refractive_index = 2.0;
+  b.set_material(m.clone());
+  let mut c = glass_sphere();
+  c.set_transform(Matrix::translation(0.0, 0.0, 0.25));
+  m.refractive_index = 2.5;
+  c.set_material(m);
+  let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = Intersections::new(vec![
+    Intersection::new(2.0, &a),
+    Intersection::new(2.75, &b),
+    Intersection::new(3.25, &c),
+    Intersection::new(4.75, &b),
+    Intersection::new(5.25, &c),
+    Intersection::new(6.0, &a)
+  ]);
+  let expected = [(1.0, 1.5), (1.5, 2.0), (2.0, 2.5), (2.5, 2.5), (2.5, 1.5), (1.5, 1.0)];
+  for (index, (n1, n2)) in expected.iter().enumerate() {
+    let comps = xs[index].prepare_computations(&r, &xs);
+    assert_eq!(comps.n1, *n1);
+    assert_eq!(comps.n2, *n2);
+  }
+}
+#[test]
+fn the_under_point_is_offset_below_the_surface() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut shape = glass_sphere();
+  shape.set_transform(Matrix::translation(0.0, 0.0, 1.0));
+  let i = Intersection::new(5.0, &shape);
+  let xs = Intersections::new(vec![i]);
+  let comps = i.prepare_computations(&r, &xs);
+  assert!(comps.under_point.z > EPSILON / 2.0);
+  assert!(comps.point.z < comps.under_point.z);
+}
+#[test]
+fn the_schlick_approximation_under_total_internal_reflection() {
+  let shape = glass_sphere();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, v), Tuple::vector(0.0, 1.0, 0.0));
+  let xs = Intersections::new(vec![Intersection::new(-v, &shape), Intersection::new(v, &shape)]);
+  let comps = xs[1].prepare_computations(&r, &xs);
+  assert_eq!(comps.schlick(), 1.0);
+}
+#[test]
+fn the_schlick_approximation_with_a_perpendicular_viewing_angle() {
+  let shape = glass_sphere();
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+  let xs = Intersections::new(vec![Intersection::new(-1.0, &shape), Intersection::new(1.0, &shape)]);
+  let comps = xs[1].prepare_computations(&r, &xs);
+  assert!((comps.schlick() - 0.04).abs() < EPSILON);
+}
+#[test]
+fn the_schlick_approximation_with_small_angle_and_n2_greater_than_n1() {
+  let shape = glass_sphere();
+  let r = Ray::new(Tuple::point(0.0, 0.99, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = Intersections::new(vec![Intersection::new(1.8589, &shape)]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  assert!((comps.schlick() - 0.48873).abs() < EPSILON);
+}
@@ -0,0 +1,18 @@
+use crate::tuple::Tuple;
+
+#[derive(Copy, Clone)]
+pub struct PointLight{ pub position: Tuple, pub intensity: Tuple }
+impl PointLight {
+  pub fn new(position: Tuple, intensity: Tuple) -> Self {
+    PointLight{position, intensity}
+  }
+}
+
+#[test]
+fn a_point_light_has_a_position_and_intensity() {
+  let intensity = Tuple::color(1.0, 1.0, 1.0);
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let light = PointLight::new(position, intensity);
+  assert!(light.position.equals(position));
+  assert!(light.intensity.equals(intensity));
+}
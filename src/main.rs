@@ -1,55 +1,168 @@
 use std::fs;
+use std::rc::Rc;
 use std::f64::consts::PI;
 
 mod tuple;
 use tuple::Tuple;
 
 mod canvas;
-use canvas::Canvas;
 
 mod matrix;
 use matrix::Matrix;
 
 mod ray;
 
+mod sphere;
+use sphere::Sphere;
+
+mod plane;
+use plane::Plane;
+
+mod cube;
+
+mod cylinder;
+
+mod cone;
+
+mod triangle;
+
+mod group;
+
+mod csg;
+
+mod bounds;
+
+mod bvh;
+
+mod obj_file;
+
+mod intersection;
+
+mod shape;
+use shape::Shape;
+
+mod noise;
+
+mod worley;
+
+mod patterns;
+use patterns::Checker;
+
+mod uv;
+
+mod materials;
+use materials::Material;
+
+mod lights;
+use lights::PointLight;
+
+mod world;
+use world::World;
+
+mod camera;
+use camera::Camera;
+
 mod transformations;
 
-// Clock
+// Scene
 
 fn main() {
-  fn rad(deg: f64) -> f64 {
-    (deg / 180.0) * PI
-  }
   let filename = "./test.ppm";
-  let color_x = Tuple::color(0.0, 100.0, 100.0);
-  let mut canvas = Canvas::new(500, 500);
-  let start = Tuple::point(0.0, -150.0, 0.0);
-  
-  let mut points = [Tuple::point(0.0, 0.0, 0.0); 14];
-  points[0] = start;
-
-  let rotation_matrix = Matrix::identity().rotate_z(PI/6.0);
-  let mut next = start;
-  for point in 1..13 {
-    let rot = rotation_matrix.clone();
-    next = rot * points[point - 1];
-    points[point] = next;
-  }
-
-  let translation_matrix = Matrix::identity().translate(250.0, 250.0, 0.0);
-  for mut clock_point in points {
-    let trans = translation_matrix.clone();
-    clock_point = trans * clock_point;
-    canvas.write_pixel(clock_point.x, clock_point.y, color_x);
-    canvas.write_pixel(clock_point.x + 1.0, clock_point.y, color_x);
-    canvas.write_pixel(clock_point.x, clock_point.y + 1.0, color_x);
-    canvas.write_pixel(clock_point.x + 1.0, clock_point.y + 1.0, color_x);
-  }
 
+  let mut floor = Plane::new();
+  let mut floor_material = Material::new();
+  floor_material.color = Tuple::color(1.0, 0.9, 0.9);
+  floor_material.specular = 0.0;
+  floor_material.pattern = Some(Rc::new(Checker::new(Tuple::color(1.0, 0.9, 0.9), Tuple::color(0.6, 0.5, 0.5))));
+  floor.set_material(floor_material.clone());
+
+  let mut backdrop = Plane::new();
+  backdrop.set_transform(Matrix::identity().rotate_x(PI/2.0).translate(0.0, 0.0, 10.0));
+  backdrop.set_material(floor_material);
+
+  let mut middle = Sphere::new();
+  middle.set_transform(Matrix::translation(-0.5, 1.0, 0.5));
+  let mut middle_material = Material::new();
+  middle_material.color = Tuple::color(0.1, 1.0, 0.5);
+  middle_material.diffuse = 0.7;
+  middle_material.specular = 0.3;
+  middle.set_material(middle_material);
+
+  let mut right = Sphere::new();
+  right.set_transform(Matrix::identity().scale(0.5, 0.5, 0.5).translate(1.5, 0.5, -0.5));
+  let mut right_material = Material::new();
+  right_material.color = Tuple::color(0.5, 1.0, 0.1);
+  right_material.diffuse = 0.7;
+  right_material.specular = 0.3;
+  right.set_material(right_material);
+
+  let mut left = Sphere::new();
+  left.set_transform(Matrix::identity().scale(0.33, 0.33, 0.33).translate(-1.5, 0.33, -0.75));
+  let mut left_material = Material::new();
+  left_material.color = Tuple::color(1.0, 0.8, 0.1);
+  left_material.diffuse = 0.7;
+  left_material.specular = 0.3;
+  left.set_material(left_material);
+
+  let mut world = World::new();
+  world.objects = vec![
+    Box::new(floor),
+    Box::new(backdrop),
+    Box::new(middle),
+    Box::new(right),
+    Box::new(left)
+  ];
+  world.lights = vec![PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0))];
+  world.build_bvh();
+
+  let mut camera = Camera::new(500, 250, PI/3.0);
+  camera.set_transform(Matrix::view_transform(
+    Tuple::point(0.0, 1.5, -5.0),
+    Tuple::point(0.0, 1.0, 0.0),
+    Tuple::vector(0.0, 1.0, 0.0)
+  ));
+
+  let canvas = camera.render(&world);
   let ppm = canvas.to_ppm();
-  fs::write(filename, ppm);
+  fs::write(filename, ppm).expect("unable to write the ppm file");
 }
 
+// Clock
+
+// fn main() {
+//   fn rad(deg: f64) -> f64 {
+//     (deg / 180.0) * PI
+//   }
+//   let filename = "./test.ppm";
+//   let color_x = Tuple::color(0.0, 100.0, 100.0);
+//   let mut canvas = Canvas::new(500, 500);
+//   let start = Tuple::point(0.0, -150.0, 0.0);
+
+//   let mut points = [Tuple::point(0.0, 0.0, 0.0); 14];
+//   points[0] = start;
+
+//   let rotation_matrix = Matrix::identity().rotate_z(PI/6.0);
+//   let mut next = start;
+//   for point in 1..13 {
+//     let rot = rotation_matrix.clone();
+//     next = rot * points[point - 1];
+//     points[point] = next;
+//   }
+
+//   let translation_matrix = Matrix::identity().translate(250.0, 250.0, 0.0);
+//   for mut clock_point in points {
+//     let trans = translation_matrix.clone();
+//     clock_point = trans * clock_point;
+//     canvas.write_pixel(clock_point.x, clock_point.y, color_x);
+//     canvas.write_pixel(clock_point.x + 1.0, clock_point.y, color_x);
+//     canvas.write_pixel(clock_point.x, clock_point.y + 1.0, color_x);
+//     canvas.write_pixel(clock_point.x + 1.0, clock_point.y + 1.0, color_x);
+//   }
+
+//   let ppm = canvas.to_ppm();
+//   fs::write(filename, ppm);
+// }
+
 // Projectile
 
 // #[derive(Copy, Clone)]
@@ -0,0 +1,177 @@
+use crate::tuple::Tuple;
+use std::rc::Rc;
+use crate::lights::PointLight;
+use crate::shape::Shape;
+use crate::patterns::Pattern;
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::patterns::Stripe;
+#[cfg(test)]
+use std::f64::consts::FRAC_1_SQRT_2;
+
+#[derive(Clone)]
+pub struct Material{
+  pub color: Tuple,
+  // when set this is used in place of color, shared so materials stay cheap to clone
+  pub pattern: Option<Rc<dyn Pattern>>,
+  pub ambient: f64,
+  pub diffuse: f64,
+  pub specular: f64,
+  pub shininess: f64,
+  // 0 is matte, 1 is a perfect mirror
+  pub reflective: f64,
+  pub transparency: f64,
+  // 1.0 is a vacuum, glass is about 1.5
+  pub refractive_index: f64
+}
+impl Material {
+  pub fn new() -> Self {
+    Material{
+      color: Tuple::color(1.0, 1.0, 1.0),
+      pattern: None,
+      ambient: 0.1,
+      diffuse: 0.9,
+      specular: 0.9,
+      shininess: 200.0,
+      reflective: 0.0,
+      transparency: 0.0,
+      refractive_index: 1.0
+    }
+  }
+}
+impl Default for Material {
+  fn default() -> Self {
+    Material::new()
+  }
+}
+
+// phong reflection: ambient + diffuse + specular, or just ambient when in shadow
+pub fn lighting(material: &Material, object: &dyn Shape, light: &PointLight, point: Tuple, eyev: Tuple, normalv: Tuple, in_shadow: bool) -> Tuple {
+  let black = Tuple::color(0.0, 0.0, 0.0);
+  let color = match &material.pattern {
+    Some(pattern) => pattern.pattern_at_shape(object, point),
+    None => material.color
+  };
+  let effective_color = color * light.intensity;
+  let lightv = (light.position - point).normalize();
+  let ambient = effective_color * material.ambient;
+  if in_shadow {
+    return ambient;
+  }
+  // a negative light_dot_normal means the light is on the other side of the surface
+  let light_dot_normal = lightv.dot(normalv);
+  if light_dot_normal < 0.0 {
+    return ambient;
+  }
+  let diffuse = effective_color * material.diffuse * light_dot_normal;
+  // a negative reflect_dot_eye means the light reflects away from the eye
+  let reflectv = (-lightv).reflect(normalv);
+  let reflect_dot_eye = reflectv.dot(eyev);
+  let specular = if reflect_dot_eye <= 0.0 {
+    black
+  }
+  else {
+    let factor = f64::powf(reflect_dot_eye, material.shininess);
+    light.intensity * material.specular * factor
+  };
+  ambient + diffuse + specular
+}
+
+#[test]
+fn the_default_material() {
+  let m = Material::new();
+  assert!(m.color.equals(Tuple::color(1.0, 1.0, 1.0)));
+  assert_eq!(m.ambient, 0.1);
+  assert_eq!(m.diffuse, 0.9);
+  assert_eq!(m.specular, 0.9);
+  assert_eq!(m.shininess, 200.0);
+}
+#[test]
+fn reflectivity_for_the_default_material() {
+  let m = Material::new();
+  assert_eq!(m.reflective, 0.0);
+}
+#[test]
+fn transparency_and_refractive_index_for_the_default_material() {
+  let m = Material::new();
+  assert_eq!(m.transparency, 0.0);
+  assert_eq!(m.refractive_index, 1.0);
+}
+#[test]
+fn lighting_with_the_eye_between_the_light_and_the_surface() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, 0.0, -1.0);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+  assert!(result.equals(Tuple::color(1.9, 1.9, 1.9)));
+}
+#[test]
+fn lighting_with_the_eye_between_light_and_surface_eye_offset_45_degrees() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+  assert!(result.equals(Tuple::color(1.0, 1.0, 1.0)));
+}
+#[test]
+fn lighting_with_eye_opposite_surface_light_offset_45_degrees() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, 0.0, -1.0);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+  assert!(result.equals(Tuple::color(0.7364, 0.7364, 0.7364)));
+}
+#[test]
+fn lighting_with_eye_in_the_path_of_the_reflection_vector() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, -FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+  assert!(result.equals(Tuple::color(1.6364, 1.6364, 1.6364)));
+}
+#[test]
+fn lighting_with_the_light_behind_the_surface() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, 0.0, -1.0);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Tuple::color(1.0, 1.0, 1.0));
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, false);
+  assert!(result.equals(Tuple::color(0.1, 0.1, 0.1)));
+}
+#[test]
+fn lighting_with_the_surface_in_shadow() {
+  let m = Material::new();
+  let position = Tuple::point(0.0, 0.0, 0.0);
+  let eyev = Tuple::vector(0.0, 0.0, -1.0);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let in_shadow = true;
+  let result = lighting(&m, &Sphere::new(), &light, position, eyev, normalv, in_shadow);
+  assert!(result.equals(Tuple::color(0.1, 0.1, 0.1)));
+}
+#[test]
+fn lighting_with_a_pattern_applied() {
+  let mut m = Material::new();
+  m.pattern = Some(Rc::new(Stripe::new(Tuple::color(1.0, 1.0, 1.0), Tuple::color(0.0, 0.0, 0.0))));
+  m.ambient = 1.0;
+  m.diffuse = 0.0;
+  m.specular = 0.0;
+  let object = Sphere::new();
+  let eyev = Tuple::vector(0.0, 0.0, -1.0);
+  let normalv = Tuple::vector(0.0, 0.0, -1.0);
+  let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let c1 = lighting(&m, &object, &light, Tuple::point(0.9, 0.0, 0.0), eyev, normalv, false);
+  let c2 = lighting(&m, &object, &light, Tuple::point(1.1, 0.0, 0.0), eyev, normalv, false);
+  assert!(c1.equals(Tuple::color(1.0, 1.0, 1.0)));
+  assert!(c2.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
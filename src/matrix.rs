@@ -19,7 +19,7 @@ impl Matrix {
     }
     for (i, row) in self.m.iter().enumerate() {
       for (j, col) in row.iter().enumerate() {
-        if col - m.m[i][j] >= EPSILON {
+        if (col - m.m[i][j]).abs() >= EPSILON {
           return false;
         }
       }
@@ -36,7 +36,7 @@ impl Matrix {
     let mut m = Matrix{m: vec![vec![0.0; 4]; 4]};
     for i in 0..4 {
       for j in 0..4 {
-        m.m[i][i] = self.m[j][i];
+        m.m[i][j] = self.m[j][i];
       }
     }
     m
@@ -167,6 +167,19 @@ impl Matrix {
   pub fn shear(&self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Matrix {
     Matrix::shearing(xy, xz, yx, yz, zx, zy) * self.clone()
   }
+  // orients the world relative to an eye at `from` looking toward `to`
+  pub fn view_transform(from: Tuple, to: Tuple, up: Tuple) -> Matrix {
+    let forward = (to - from).normalize();
+    let left = forward.cross(up.normalize());
+    let true_up = left.cross(forward);
+    let orientation = Matrix{m: vec![
+      vec![left.x, left.y, left.z, 0.0],
+      vec![true_up.x, true_up.y, true_up.z, 0.0],
+      vec![-forward.x, -forward.y, -forward.z, 0.0],
+      vec![0.0, 0.0, 0.0, 1.0]
+    ]};
+    orientation * Matrix::translation(-from.x, -from.y, -from.z)
+  }
 }
 impl ops::Mul<Self> for Matrix {
   type Output = Self;
@@ -188,7 +201,14 @@ impl ops::Mul<Self> for Matrix {
 impl ops::Mul<Tuple> for Matrix {
   type Output = Tuple;
   fn mul(self, _rhs: Tuple) -> Self::Output {
-    let a = self.m;
+    &self * _rhs
+  }
+}
+// multiply without giving up the matrix, shapes reuse theirs for every ray
+impl ops::Mul<Tuple> for &Matrix {
+  type Output = Tuple;
+  fn mul(self, _rhs: Tuple) -> Self::Output {
+    let a = &self.m;
     let b = _rhs;
     Tuple::new(
       a[0][0] * b.x + a[0][1] * b.y + a[0][2] * b.z + a[0][3] * b.w,
@@ -522,3 +542,17 @@ fn multiplying_a_product_by_its_inverse() {
   let c = a * b_clone;
   assert!((c * b.inverse()).equals(a_clone));
 }
+#[test]
+fn transposing_a_matrix_moves_every_element() {
+  let a = Matrix{m: vec![
+    vec![1.0, 2.0, 3.0, 4.0],
+    vec![5.0, 6.0, 7.0, 8.0],
+    vec![9.0, 10.0, 11.0, 12.0],
+    vec![13.0, 14.0, 15.0, 16.0]
+  ]};
+  let t = a.transpose();
+  assert_eq!(t.m[0][3], 13.0);
+  assert_eq!(t.m[3][0], 4.0);
+  assert_eq!(t.m[1][2], 10.0);
+  assert!(!t.equals(a));
+}
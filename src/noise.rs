@@ -0,0 +1,199 @@
+use crate::tuple::Tuple;
+#[cfg(test)]
+use crate::tuple::EPSILON;
+
+// seeds the shuffle of the permutation table so every run gets the same noise
+pub const DEFAULT_SEED: u64 = 0x2545f4914f6cdd1d;
+// each octave doubles the frequency and halves the amplitude of the one before
+const LACUNARITY: f64 = 2.0;
+const GAIN: f64 = 0.5;
+
+// ken perlin's improved gradient noise, smooth and roughly within -1..1, zero on every lattice point
+// the same seed always produces the same noise
+#[derive(Clone)]
+pub struct Perlin{ perm: Vec<usize> }
+impl Perlin {
+  pub fn new() -> Self {
+    Perlin::with_seed(DEFAULT_SEED)
+  }
+  pub fn with_seed(seed: u64) -> Self {
+    // fisher-yates over 0..256 driven by xorshift, then doubled so lookups never need to wrap.
+    // xorshift gets stuck on zero, so that seed is nudged
+    let mut table: Vec<usize> = (0..256).collect();
+    let mut state = if seed == 0 { DEFAULT_SEED } else { seed };
+    for i in (1..256).rev() {
+      state ^= state << 13;
+      state ^= state >> 7;
+      state ^= state << 17;
+      let j = (state % (i as u64 + 1)) as usize;
+      table.swap(i, j);
+    }
+    let perm = table.iter().chain(table.iter()).copied().collect();
+    Perlin{perm}
+  }
+  pub fn noise(&self, point: Tuple) -> f64 {
+    let (xi, yi, zi) = (lattice(point.x), lattice(point.y), lattice(point.z));
+    let (x, y, z) = (point.x - point.x.floor(), point.y - point.y.floor(), point.z - point.z.floor());
+    let (u, v, w) = (fade(x), fade(y), fade(z));
+    let p = &self.perm;
+    // hash each corner of the unit cube the point is in
+    let a = p[xi] + yi;
+    let aa = p[a] + zi;
+    let ab = p[a + 1] + zi;
+    let b = p[xi + 1] + yi;
+    let ba = p[b] + zi;
+    let bb = p[b + 1] + zi;
+    lerp(w,
+      lerp(v,
+        lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+        lerp(u, grad(p[ab], x, y - 1.0, z), grad(p[bb], x - 1.0, y - 1.0, z))),
+      lerp(v,
+        lerp(u, grad(p[aa + 1], x, y, z - 1.0), grad(p[ba + 1], x - 1.0, y, z - 1.0)),
+        lerp(u, grad(p[ab + 1], x, y - 1.0, z - 1.0), grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0))))
+  }
+  // fractal brownian motion: octaves of noise at rising frequency and falling amplitude,
+  // normalized back to -1..1. like turbulence it's clamped, since the noise can stray a little past 1
+  pub fn fbm(&self, point: Tuple, octaves: usize) -> f64 {
+    self.octaves(point, octaves, |n| n).clamp(-1.0, 1.0)
+  }
+  // like fbm but summing the absolute value, which gives sharp creases instead of smooth hills.
+  // the noise can stray a little past 1, so the result is clamped to 0..1
+  pub fn turbulence(&self, point: Tuple, octaves: usize) -> f64 {
+    self.octaves(point, octaves, f64::abs).min(1.0)
+  }
+  fn octaves(&self, point: Tuple, octaves: usize, shape: impl Fn(f64) -> f64) -> f64 {
+    let mut sum = 0.0;
+    let mut total = 0.0;
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    for _ in 0..octaves {
+      let p = Tuple::point(point.x * frequency, point.y * frequency, point.z * frequency);
+      sum += shape(self.noise(p)) * amplitude;
+      total += amplitude;
+      frequency *= LACUNARITY;
+      amplitude *= GAIN;
+    }
+    if total == 0.0 {
+      return 0.0;
+    }
+    sum / total
+  }
+}
+impl Default for Perlin {
+  fn default() -> Self {
+    Perlin::new()
+  }
+}
+
+// the lattice repeats every 256 units
+fn lattice(n: f64) -> usize {
+  (n.floor() as i64).rem_euclid(256) as usize
+}
+
+// 6t^5 - 15t^4 + 10t^3, flat at both ends so neighbouring cells join smoothly
+fn fade(t: f64) -> f64 {
+  t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+  a + t * (b - a)
+}
+
+// dot product of x, y, z with one of twelve edge gradients picked by the hash
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+  let h = hash & 15;
+  let u = if h < 8 { x } else { y };
+  let v = if h < 4 {
+    y
+  }
+  else if h == 12 || h == 14 {
+    x
+  }
+  else {
+    z
+  };
+  (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[test]
+fn noise_is_zero_on_the_lattice() {
+  let perlin = Perlin::new();
+  assert_eq!(perlin.noise(Tuple::point(0.0, 0.0, 0.0)), 0.0);
+  assert_eq!(perlin.noise(Tuple::point(3.0, -7.0, 12.0)), 0.0);
+}
+#[test]
+fn noise_is_the_same_every_time() {
+  let p = Tuple::point(1.3, 2.7, -0.4);
+  assert_eq!(Perlin::new().noise(p), Perlin::new().noise(p));
+}
+#[test]
+fn noise_stays_within_range() {
+  let perlin = Perlin::new();
+  let mut varies = false;
+  for i in 0..1000 {
+    let t = i as f64 * 0.137;
+    let n = perlin.noise(Tuple::point(t, t * 0.7 - 3.0, 11.0 - t * 1.3));
+    assert!((-1.0..=1.0).contains(&n));
+    varies |= n.abs() > 0.1;
+  }
+  assert!(varies);
+}
+#[test]
+fn noise_is_continuous() {
+  let perlin = Perlin::new();
+  let p = Tuple::point(4.5, 1.25, -2.75);
+  let q = p + Tuple::vector(0.0001, 0.0001, 0.0001);
+  assert!((perlin.noise(p) - perlin.noise(q)).abs() < 0.001);
+}
+#[test]
+fn the_same_seed_gives_the_same_noise() {
+  let a = Perlin::with_seed(42);
+  let b = Perlin::with_seed(42);
+  for i in 0..100 {
+    let t = i as f64 * 0.31;
+    let p = Tuple::point(t, -t * 0.5, t * 1.7 + 0.2);
+    assert_eq!(a.noise(p), b.noise(p));
+  }
+}
+#[test]
+fn different_seeds_give_different_noise() {
+  let a = Perlin::with_seed(1);
+  let b = Perlin::with_seed(2);
+  let differs = (0..100).any(|i| {
+    let t = i as f64 * 0.31;
+    let p = Tuple::point(t, -t * 0.5, t * 1.7 + 0.2);
+    a.noise(p) != b.noise(p)
+  });
+  assert!(differs);
+}
+#[test]
+fn the_default_noise_uses_the_default_seed() {
+  let p = Tuple::point(0.4, 1.9, -5.3);
+  assert_eq!(Perlin::new().noise(p), Perlin::with_seed(DEFAULT_SEED).noise(p));
+}
+#[test]
+fn fbm_with_one_octave_is_plain_noise() {
+  let perlin = Perlin::new();
+  let p = Tuple::point(2.3, 0.7, -1.1);
+  assert!((perlin.fbm(p, 1) - perlin.noise(p)).abs() < EPSILON);
+}
+#[test]
+fn fbm_with_no_octaves_is_zero() {
+  let perlin = Perlin::new();
+  assert_eq!(perlin.fbm(Tuple::point(2.3, 0.7, -1.1), 0), 0.0);
+}
+#[test]
+fn fbm_and_turbulence_stay_within_range() {
+  let perlin = Perlin::new();
+  for i in 0..500 {
+    let t = i as f64 * 0.173;
+    let p = Tuple::point(t, 3.0 - t * 0.6, t * 1.1);
+    assert!((-1.0..=1.0).contains(&perlin.fbm(p, 6)));
+    assert!((0.0..=1.0).contains(&perlin.turbulence(p, 6)));
+  }
+}
+#[test]
+fn turbulence_is_zero_on_the_lattice() {
+  let perlin = Perlin::new();
+  assert_eq!(perlin.turbulence(Tuple::point(1.0, -2.0, 5.0), 4), 0.0);
+}
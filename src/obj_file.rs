@@ -0,0 +1,468 @@
+use std::fmt;
+use crate::tuple::Tuple;
+use crate::shape::Shape;
+use crate::triangle::{Triangle, SmoothTriangle};
+use crate::group::Group;
+#[cfg(test)]
+use crate::ray::Ray;
+
+#[derive(Debug, PartialEq)]
+pub enum ObjError {
+  // a face referred to a vertex, texture or normal that hasn't been declared
+  IndexOutOfRange{ line: usize, index: i64 },
+  // a face index that isn't a whole number
+  MalformedIndex{ line: usize, index: String },
+  // a vertex, normal or texture line with a value that isn't a number or the wrong number of values
+  MalformedRecord{ line: usize }
+}
+impl fmt::Display for ObjError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ObjError::IndexOutOfRange{line, index} => write!(f, "line {}: index {} is out of range", line, index),
+      ObjError::MalformedIndex{line, index} => write!(f, "line {}: index {:?} is not a number", line, index),
+      ObjError::MalformedRecord{line} => write!(f, "line {}: malformed record", line)
+    }
+  }
+}
+
+// faces get smooth triangles only when every corner names a normal
+pub enum ObjTriangle {
+  Flat(Triangle),
+  Smooth(SmoothTriangle)
+}
+impl ObjTriangle {
+  pub fn into_shape(self) -> Box<dyn Shape> {
+    match self {
+      ObjTriangle::Flat(t) => Box::new(t),
+      ObjTriangle::Smooth(t) => Box::new(t)
+    }
+  }
+}
+
+pub struct ObjGroup{ pub name: String, pub triangles: Vec<ObjTriangle> }
+
+pub struct ObjFile{
+  pub vertices: Vec<Tuple>,
+  pub normals: Vec<Tuple>,
+  pub textures: Vec<Tuple>,
+  // faces that come before any g statement
+  pub default_group: Vec<ObjTriangle>,
+  pub groups: Vec<ObjGroup>,
+  pub ignored: usize
+}
+impl ObjFile {
+  pub fn group(&self, name: &str) -> Option<&ObjGroup> {
+    self.groups.iter().find(|g| g.name == name)
+  }
+  // ungrouped faces sit directly in the result, each named group becomes a child group.
+  // the bvh is built before handing it back, which reorders the children
+  pub fn into_group(self) -> Group {
+    let mut root = Group::new();
+    for triangle in self.default_group {
+      root.add_child(triangle.into_shape());
+    }
+    for obj_group in self.groups {
+      let mut group = Group::new();
+      for triangle in obj_group.triangles {
+        group.add_child(triangle.into_shape());
+      }
+      root.add_child(Box::new(group));
+    }
+    root.build_bvh();
+    root
+  }
+}
+
+// obj indices are 1-based, negative ones count back from the latest entry
+fn resolve(index: i64, len: usize, line: usize) -> Result<usize, ObjError> {
+  let resolved = if index < 0 { len as i64 + index } else { index - 1 };
+  if resolved < 0 || resolved >= len as i64 {
+    return Err(ObjError::IndexOutOfRange{line, index});
+  }
+  Ok(resolved as usize)
+}
+
+fn parse_floats(args: &[&str]) -> Option<Vec<f64>> {
+  args.iter().map(|a| a.parse::<f64>().ok()).collect()
+}
+
+fn parse_index(index: &str, line: usize) -> Result<i64, ObjError> {
+  index.parse::<i64>().map_err(|_| ObjError::MalformedIndex{line, index: index.to_string()})
+}
+
+// a face corner is v, v/vt, v//vn or v/vt/vn, an empty vt or vn just isn't there
+fn parse_corner(corner: &str, line: usize) -> Result<(i64, Option<i64>, Option<i64>), ObjError> {
+  let mut parts = corner.split('/');
+  let vertex = parse_index(parts.next().unwrap_or(""), line)?;
+  let mut optional = || match parts.next() {
+    Some(index) if !index.is_empty() => parse_index(index, line).map(Some),
+    _ => Ok(None)
+  };
+  let texture = optional()?;
+  let normal = optional()?;
+  Ok((vertex, texture, normal))
+}
+
+pub fn parse_obj_file(contents: &str) -> Result<ObjFile, ObjError> {
+  let mut obj = ObjFile{
+    vertices: vec![],
+    normals: vec![],
+    textures: vec![],
+    default_group: vec![],
+    groups: vec![],
+    ignored: 0
+  };
+  let mut current_group: Option<usize> = None;
+  for (i, raw) in contents.lines().enumerate() {
+    let line = i + 1;
+    let words: Vec<&str> = raw.split_whitespace().collect();
+    if words.is_empty() {
+      continue;
+    }
+    let args = &words[1..];
+    match words[0] {
+      "v" | "vn" | "vt" => {
+        let values = parse_floats(args).ok_or(ObjError::MalformedRecord{line})?;
+        match (words[0], values.len()) {
+          // the optional w of a vertex only matters for rational curves, so it's dropped
+          ("v", 3) | ("v", 4) => obj.vertices.push(Tuple::point(values[0], values[1], values[2])),
+          ("vn", 3) => obj.normals.push(Tuple::vector(values[0], values[1], values[2])),
+          ("vt", 1) => obj.textures.push(Tuple::point(values[0], 0.0, 0.0)),
+          ("vt", 2) => obj.textures.push(Tuple::point(values[0], values[1], 0.0)),
+          ("vt", 3) => obj.textures.push(Tuple::point(values[0], values[1], values[2])),
+          _ => return Err(ObjError::MalformedRecord{line})
+        }
+      }
+      "f" => {
+        let corners = args.iter().map(|c| parse_corner(c, line)).collect::<Result<Vec<_>, _>>()?;
+        if corners.len() < 3 {
+          obj.ignored += 1;
+          continue;
+        }
+        let mut points = vec![];
+        let mut normals = vec![];
+        for (vertex, texture, normal) in corners.iter() {
+          points.push(obj.vertices[resolve(*vertex, obj.vertices.len(), line)?]);
+          // triangles don't carry texture coordinates yet, but a bad index is still an error
+          if let Some(t) = texture {
+            resolve(*t, obj.textures.len(), line)?;
+          }
+          if let Some(n) = normal {
+            normals.push(obj.normals[resolve(*n, obj.normals.len(), line)?]);
+          }
+        }
+        let smooth = normals.len() == points.len();
+        let group = match current_group {
+          Some(g) => &mut obj.groups[g].triangles,
+          None => &mut obj.default_group
+        };
+        // polygons are split into a fan of triangles around the first vertex
+        for j in 1..points.len() - 1 {
+          if smooth {
+            group.push(ObjTriangle::Smooth(SmoothTriangle::new(
+              points[0], points[j], points[j + 1],
+              normals[0], normals[j], normals[j + 1]
+            )));
+          }
+          else {
+            group.push(ObjTriangle::Flat(Triangle::new(points[0], points[j], points[j + 1])));
+          }
+        }
+      }
+      "g" => {
+        let name = args.join(" ");
+        current_group = match obj.groups.iter().position(|g| g.name == name) {
+          Some(g) => Some(g),
+          None => {
+            obj.groups.push(ObjGroup{name, triangles: vec![]});
+            Some(obj.groups.len() - 1)
+          }
+        };
+      }
+      _ => obj.ignored += 1
+    }
+  }
+  Ok(obj)
+}
+
+#[cfg(test)]
+fn flat(t: &ObjTriangle) -> &Triangle {
+  match t {
+    ObjTriangle::Flat(t) => t,
+    ObjTriangle::Smooth(_) => panic!("expected a flat triangle")
+  }
+}
+#[cfg(test)]
+fn smooth(t: &ObjTriangle) -> &SmoothTriangle {
+  match t {
+    ObjTriangle::Smooth(t) => t,
+    ObjTriangle::Flat(_) => panic!("expected a smooth triangle")
+  }
+}
+
+#[test]
+fn ignoring_unrecognized_lines() {
+  let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+  let parser = parse_obj_file(gibberish).unwrap();
+  assert_eq!(parser.ignored, 5);
+}
+#[test]
+fn vertex_records() {
+  let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+  let parser = parse_obj_file(file).unwrap();
+  assert!(parser.vertices[0].equals(Tuple::point(-1.0, 1.0, 0.0)));
+  assert!(parser.vertices[1].equals(Tuple::point(-1.0, 0.5, 0.0)));
+  assert!(parser.vertices[2].equals(Tuple::point(1.0, 0.0, 0.0)));
+  assert!(parser.vertices[3].equals(Tuple::point(1.0, 1.0, 0.0)));
+}
+#[test]
+fn parsing_triangle_faces() {
+  let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+  let parser = parse_obj_file(file).unwrap();
+  let t1 = flat(&parser.default_group[0]);
+  let t2 = flat(&parser.default_group[1]);
+  assert!(t1.p1.equals(parser.vertices[0]));
+  assert!(t1.p2.equals(parser.vertices[1]));
+  assert!(t1.p3.equals(parser.vertices[2]));
+  assert!(t2.p1.equals(parser.vertices[0]));
+  assert!(t2.p2.equals(parser.vertices[2]));
+  assert!(t2.p3.equals(parser.vertices[3]));
+}
+#[test]
+fn triangulating_polygons() {
+  let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+  let parser = parse_obj_file(file).unwrap();
+  assert_eq!(parser.default_group.len(), 3);
+  let t1 = flat(&parser.default_group[0]);
+  let t2 = flat(&parser.default_group[1]);
+  let t3 = flat(&parser.default_group[2]);
+  assert!(t1.p1.equals(parser.vertices[0]));
+  assert!(t1.p2.equals(parser.vertices[1]));
+  assert!(t1.p3.equals(parser.vertices[2]));
+  assert!(t2.p1.equals(parser.vertices[0]));
+  assert!(t2.p2.equals(parser.vertices[2]));
+  assert!(t2.p3.equals(parser.vertices[3]));
+  assert!(t3.p1.equals(parser.vertices[0]));
+  assert!(t3.p2.equals(parser.vertices[3]));
+  assert!(t3.p3.equals(parser.vertices[4]));
+}
+#[test]
+fn triangles_in_groups() {
+  let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+  let parser = parse_obj_file(file).unwrap();
+  let t1 = flat(&parser.group("FirstGroup").unwrap().triangles[0]);
+  let t2 = flat(&parser.group("SecondGroup").unwrap().triangles[0]);
+  assert!(t1.p1.equals(parser.vertices[0]));
+  assert!(t1.p2.equals(parser.vertices[1]));
+  assert!(t1.p3.equals(parser.vertices[2]));
+  assert!(t2.p1.equals(parser.vertices[0]));
+  assert!(t2.p2.equals(parser.vertices[2]));
+  assert!(t2.p3.equals(parser.vertices[3]));
+  assert!(parser.default_group.is_empty());
+}
+#[test]
+fn vertex_normal_and_texture_records() {
+  let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3
+vt 0.5 0.25";
+  let parser = parse_obj_file(file).unwrap();
+  assert!(parser.normals[0].equals(Tuple::vector(0.0, 0.0, 1.0)));
+  assert!(parser.normals[1].equals(Tuple::vector(0.707, 0.0, -0.707)));
+  assert!(parser.normals[2].equals(Tuple::vector(1.0, 2.0, 3.0)));
+  assert!(parser.textures[0].equals(Tuple::point(0.5, 0.25, 0.0)));
+  assert_eq!(parser.ignored, 0);
+}
+#[test]
+fn faces_with_normals() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vt 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/1/3 2/1/1 3/1/2";
+  let parser = parse_obj_file(file).unwrap();
+  for triangle in parser.default_group.iter() {
+    let t = smooth(triangle);
+    assert!(t.p1.equals(parser.vertices[0]));
+    assert!(t.p2.equals(parser.vertices[1]));
+    assert!(t.p3.equals(parser.vertices[2]));
+    assert!(t.n1.equals(parser.normals[2]));
+    assert!(t.n2.equals(parser.normals[0]));
+    assert!(t.n3.equals(parser.normals[1]));
+  }
+  assert_eq!(parser.default_group.len(), 2);
+}
+#[test]
+fn faces_with_texture_indices_only_stay_flat() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+f 1/1 2/1 3/1";
+  let parser = parse_obj_file(file).unwrap();
+  flat(&parser.default_group[0]);
+}
+#[test]
+fn negative_indices_count_back_from_the_latest_vertex() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f -3 -2 -1";
+  let parser = parse_obj_file(file).unwrap();
+  let t = flat(&parser.default_group[0]);
+  assert!(t.p1.equals(parser.vertices[0]));
+  assert!(t.p3.equals(parser.vertices[2]));
+}
+#[test]
+fn an_out_of_range_vertex_index_is_an_error() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+
+f 1 2 4";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::IndexOutOfRange{line: 5, index: 4});
+  assert_eq!(err.to_string(), "line 5: index 4 is out of range");
+}
+#[test]
+fn an_out_of_range_normal_index_is_an_error() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vn 0 0 1
+f 1//1 2//1 3//2";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::IndexOutOfRange{line: 5, index: 2});
+}
+#[test]
+fn a_zero_index_is_out_of_range() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f 0 1 2";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::IndexOutOfRange{line: 4, index: 0});
+}
+#[test]
+fn converting_an_obj_file_to_a_group() {
+  let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+  let g = parse_obj_file(file).unwrap().into_group();
+  assert_eq!(g.children().len(), 3);
+  assert_eq!(g.children()[1].children().len(), 1);
+  assert_eq!(g.children()[2].children().len(), 1);
+}
+#[test]
+fn a_converted_group_can_be_intersected() {
+  let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+f 1 2 3
+g Top
+f 1 3 4";
+  let g = parse_obj_file(file).unwrap().into_group();
+  let r = Ray::new(Tuple::point(-0.5, 0.2, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = g.intersect(&r);
+  assert_eq!(xs.len(), 1);
+  assert_eq!(xs[0].t, 5.0);
+  let r = Ray::new(Tuple::point(0.5, 0.8, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert_eq!(g.intersect(&r).len(), 1);
+}
+#[test]
+fn vertices_with_a_w_component_keep_their_place() {
+  let file = "v 0 1 0 1
+v -1 0 0
+v 1 0 0 0.5
+vt 0.25
+f 1 2 3";
+  let parser = parse_obj_file(file).unwrap();
+  assert_eq!(parser.vertices.len(), 3);
+  assert_eq!(parser.ignored, 0);
+  assert!(parser.textures[0].equals(Tuple::point(0.25, 0.0, 0.0)));
+  let t = flat(&parser.default_group[0]);
+  assert!(t.p1.equals(Tuple::point(0.0, 1.0, 0.0)));
+  assert!(t.p3.equals(Tuple::point(1.0, 0.0, 0.0)));
+}
+#[test]
+fn an_out_of_range_texture_index_is_an_error() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+vn 0 0 1
+f 1/99/1 2/99/1 3/99/1";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::IndexOutOfRange{line: 6, index: 99});
+}
+#[test]
+fn a_malformed_index_is_an_error() {
+  let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+vn 0 0 1
+f 1/x/1 2/1/1 3/1/1";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::MalformedIndex{line: 6, index: "x".to_string()});
+  assert_eq!(err.to_string(), "line 6: index \"x\" is not a number");
+}
+#[test]
+fn a_malformed_vertex_is_an_error() {
+  let file = "v 1 x 2
+v -1 0 0
+v 1 0 0
+f 1 2 3";
+  let err = parse_obj_file(file).err().unwrap();
+  assert_eq!(err, ObjError::MalformedRecord{line: 1});
+  assert_eq!(err.to_string(), "line 1: malformed record");
+}
+#[test]
+fn a_vertex_with_too_few_values_is_an_error() {
+  let file = "v 0 1 0
+v -1 0 0
+vn 0 1";
+  assert_eq!(parse_obj_file(file).err().unwrap(), ObjError::MalformedRecord{line: 3});
+}
@@ -0,0 +1,651 @@
+use std::rc::Rc;
+use std::f64::consts::PI;
+use crate::tuple::Tuple;
+use crate::matrix::Matrix;
+use crate::shape::Shape;
+use crate::noise::Perlin;
+use crate::worley::{Worley, Feature};
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::tuple::EPSILON;
+
+// a pattern's placement on the object, inverse is kept alongside so lookups needn't invert per point
+#[derive(Clone)]
+pub struct PatternData{ transform: Matrix, inverse: Matrix }
+impl PatternData {
+  pub fn new() -> Self {
+    PatternData{transform: Matrix::identity(), inverse: Matrix::identity()}
+  }
+}
+impl Default for PatternData {
+  fn default() -> Self {
+    PatternData::new()
+  }
+}
+
+pub trait Pattern {
+  fn data(&self) -> &PatternData;
+  fn data_mut(&mut self) -> &mut PatternData;
+  // the point handed to this is already in pattern space
+  fn pattern_at(&self, point: Tuple) -> Tuple;
+
+  fn transform(&self) -> &Matrix {
+    &self.data().transform
+  }
+  fn inverse(&self) -> &Matrix {
+    &self.data().inverse
+  }
+  fn set_transform(&mut self, transform: Matrix) {
+    let data = self.data_mut();
+    data.inverse = transform.inverse();
+    data.transform = transform;
+  }
+  // world space goes through the object (and any groups around it) first, then the pattern
+  fn pattern_at_shape(&self, object: &dyn Shape, world_point: Tuple) -> Tuple {
+    self.pattern_at_parent(object.world_to_object(world_point))
+  }
+  // the point is in the space of whatever holds this pattern, an object or an enclosing pattern
+  fn pattern_at_parent(&self, point: Tuple) -> Tuple {
+    self.pattern_at(self.inverse() * point)
+  }
+}
+
+// the same color everywhere, what plain colors become when they're handed to another pattern
+pub struct Solid{ data: PatternData, pub color: Tuple }
+impl Solid {
+  pub fn new(color: Tuple) -> Self {
+    Solid{data: PatternData::new(), color}
+  }
+}
+impl Pattern for Solid {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, _point: Tuple) -> Tuple {
+    self.color
+  }
+}
+
+// alternates between a and b every unit along x
+pub struct Stripe{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern> }
+impl Stripe {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Stripe::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Stripe{data: PatternData::new(), a, b}
+  }
+}
+impl Pattern for Stripe {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    if point.x.floor() % 2.0 == 0.0 {
+      self.a.pattern_at_parent(point)
+    }
+    else {
+      self.b.pattern_at_parent(point)
+    }
+  }
+}
+
+// blends linearly from a to b as x goes from 0 to 1
+pub struct Gradient{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern> }
+impl Gradient {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Gradient::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Gradient{data: PatternData::new(), a, b}
+  }
+}
+impl Pattern for Gradient {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let fraction = point.x - point.x.floor();
+    mix(self.a.pattern_at_parent(point), self.b.pattern_at_parent(point), fraction)
+  }
+}
+
+// concentric rings around the y axis, one unit wide
+pub struct Ring{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern> }
+impl Ring {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Ring::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Ring{data: PatternData::new(), a, b}
+  }
+}
+impl Pattern for Ring {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let distance = f64::sqrt(point.x * point.x + point.z * point.z);
+    if distance.floor() % 2.0 == 0.0 {
+      self.a.pattern_at_parent(point)
+    }
+    else {
+      self.b.pattern_at_parent(point)
+    }
+  }
+}
+
+// unit cubes alternating in all three dimensions
+pub struct Checker{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern> }
+impl Checker {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Checker::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Checker{data: PatternData::new(), a, b}
+  }
+}
+impl Pattern for Checker {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let sum = point.x.floor() + point.y.floor() + point.z.floor();
+    if sum % 2.0 == 0.0 {
+      self.a.pattern_at_parent(point)
+    }
+    else {
+      self.b.pattern_at_parent(point)
+    }
+  }
+}
+
+// the even mix of two patterns
+pub struct Blend{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern> }
+impl Blend {
+  pub fn new(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Blend{data: PatternData::new(), a, b}
+  }
+}
+impl Pattern for Blend {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    (self.a.pattern_at_parent(point) + self.b.pattern_at_parent(point)) * 0.5
+  }
+}
+
+// nudges the lookup point by up to scale in each direction before sampling the inner pattern,
+// which roughens the straight edges of the other patterns
+pub struct Perturb{ data: PatternData, pub pattern: Rc<dyn Pattern>, pub scale: f64, pub noise: Perlin }
+impl Perturb {
+  pub fn new(pattern: Rc<dyn Pattern>, scale: f64) -> Self {
+    Perturb{data: PatternData::new(), pattern, scale, noise: Perlin::new()}
+  }
+}
+impl Pattern for Perturb {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    // sample the noise at offset points so the three axes don't move in lockstep
+    let jitter = Tuple::vector(
+      self.noise.noise(point),
+      self.noise.noise(point + Tuple::vector(0.0, 0.0, 17.0)),
+      self.noise.noise(point + Tuple::vector(0.0, 0.0, 43.0))
+    );
+    self.pattern.pattern_at_parent(point + jitter * self.scale)
+  }
+}
+
+// veins of b running through a, bands along x bent out of shape by turbulence
+pub struct Marble{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern>, pub turbulence: f64, pub octaves: usize, pub noise: Perlin }
+impl Marble {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Marble::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Marble{data: PatternData::new(), a, b, turbulence: 4.0, octaves: 6, noise: Perlin::new()}
+  }
+}
+impl Pattern for Marble {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let offset = self.turbulence * self.noise.turbulence(point, self.octaves);
+    let t = (1.0 + f64::sin((point.x + offset) * PI)) / 2.0;
+    mix(self.a.pattern_at_parent(point), self.b.pattern_at_parent(point), t)
+  }
+}
+
+// growth rings around the y axis, one unit apart, wobbled by a little noise
+pub struct Wood{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern>, pub grain: f64, pub noise: Perlin }
+impl Wood {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Wood::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Wood{data: PatternData::new(), a, b, grain: 0.2, noise: Perlin::new()}
+  }
+}
+impl Pattern for Wood {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let distance = f64::sqrt(point.x * point.x + point.z * point.z) + self.grain * self.noise.noise(point);
+    let t = distance - distance.floor();
+    mix(self.a.pattern_at_parent(point), self.b.pattern_at_parent(point), t)
+  }
+}
+
+// soft billows of b over a from fractal noise
+pub struct Clouds{ data: PatternData, pub a: Rc<dyn Pattern>, pub b: Rc<dyn Pattern>, pub octaves: usize, pub noise: Perlin }
+impl Clouds {
+  pub fn new(a: Tuple, b: Tuple) -> Self {
+    Clouds::with_patterns(Rc::new(Solid::new(a)), Rc::new(Solid::new(b)))
+  }
+  pub fn with_patterns(a: Rc<dyn Pattern>, b: Rc<dyn Pattern>) -> Self {
+    Clouds{data: PatternData::new(), a, b, octaves: 6, noise: Perlin::new()}
+  }
+}
+impl Pattern for Clouds {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let t = ((self.noise.fbm(point, self.octaves) + 1.0) / 2.0).clamp(0.0, 1.0);
+    mix(self.a.pattern_at_parent(point), self.b.pattern_at_parent(point), t)
+  }
+}
+
+// colors pinned at positions along a line, anything in between is interpolated
+// and anything past either end takes the color at that end
+pub struct ColorRamp{ stops: Vec<(f64, Tuple)> }
+impl ColorRamp {
+  pub fn new(mut stops: Vec<(f64, Tuple)>) -> Self {
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    ColorRamp{stops}
+  }
+  pub fn color_at(&self, t: f64) -> Tuple {
+    let (first, last) = match (self.stops.first(), self.stops.last()) {
+      (Some(first), Some(last)) => (first, last),
+      _ => return Tuple::color(0.0, 0.0, 0.0)
+    };
+    if t <= first.0 {
+      return first.1;
+    }
+    if t >= last.0 {
+      return last.1;
+    }
+    for pair in self.stops.windows(2) {
+      let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+      if t <= t1 {
+        return mix(c0, c1, (t - t0) / (t1 - t0));
+      }
+    }
+    last.1
+  }
+}
+
+// cellular noise run through a color ramp, for stone, scales, cracked mud and voronoi tiles
+pub struct Cellular{ data: PatternData, pub worley: Worley, pub feature: Feature, pub ramp: ColorRamp }
+impl Cellular {
+  pub fn new(feature: Feature, ramp: ColorRamp) -> Self {
+    Cellular{data: PatternData::new(), worley: Worley::new(), feature, ramp}
+  }
+}
+impl Pattern for Cellular {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    self.ramp.color_at(self.worley.value(point, self.feature))
+  }
+}
+
+// t of 0 is all a, 1 is all b
+fn mix(a: Tuple, b: Tuple, t: f64) -> Tuple {
+  a + (b - a) * t
+}
+
+// hands the pattern space point straight back as a color so tests can see the transforms
+#[cfg(test)]
+pub struct TestPattern{ data: PatternData }
+#[cfg(test)]
+impl TestPattern {
+  pub fn new() -> Self {
+    TestPattern{data: PatternData::new()}
+  }
+}
+#[cfg(test)]
+impl Pattern for TestPattern {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    Tuple::color(point.x, point.y, point.z)
+  }
+}
+
+#[cfg(test)]
+fn white() -> Tuple {
+  Tuple::color(1.0, 1.0, 1.0)
+}
+#[cfg(test)]
+fn black() -> Tuple {
+  Tuple::color(0.0, 0.0, 0.0)
+}
+
+#[test]
+fn creating_a_stripe_pattern() {
+  let pattern = Stripe::new(white(), black());
+  assert!(pattern.a.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.b.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(black()));
+}
+#[test]
+fn a_stripe_pattern_is_constant_in_y() {
+  let pattern = Stripe::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 1.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 2.0, 0.0)).equals(white()));
+}
+#[test]
+fn a_stripe_pattern_is_constant_in_z() {
+  let pattern = Stripe::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 1.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 2.0)).equals(white()));
+}
+#[test]
+fn a_stripe_pattern_alternates_in_x() {
+  let pattern = Stripe::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.9, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(1.0, 0.0, 0.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(-0.1, 0.0, 0.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(-1.0, 0.0, 0.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(-1.1, 0.0, 0.0)).equals(white()));
+}
+#[test]
+fn the_default_pattern_transformation() {
+  let pattern = TestPattern::new();
+  assert!(pattern.transform().equals(Matrix::identity()));
+}
+#[test]
+fn assigning_a_transformation() {
+  let mut pattern = TestPattern::new();
+  pattern.set_transform(Matrix::translation(1.0, 2.0, 3.0));
+  assert!(pattern.transform().equals(Matrix::translation(1.0, 2.0, 3.0)));
+}
+#[test]
+fn a_pattern_with_an_object_transformation() {
+  let mut shape = Sphere::new();
+  shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let pattern = TestPattern::new();
+  let c = pattern.pattern_at_shape(&shape, Tuple::point(2.0, 3.0, 4.0));
+  assert!(c.equals(Tuple::color(1.0, 1.5, 2.0)));
+}
+#[test]
+fn a_pattern_with_a_pattern_transformation() {
+  let shape = Sphere::new();
+  let mut pattern = TestPattern::new();
+  pattern.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let c = pattern.pattern_at_shape(&shape, Tuple::point(2.0, 3.0, 4.0));
+  assert!(c.equals(Tuple::color(1.0, 1.5, 2.0)));
+}
+#[test]
+fn a_pattern_with_both_an_object_and_a_pattern_transformation() {
+  let mut shape = Sphere::new();
+  shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let mut pattern = TestPattern::new();
+  pattern.set_transform(Matrix::translation(0.5, 1.0, 1.5));
+  let c = pattern.pattern_at_shape(&shape, Tuple::point(2.5, 3.0, 3.5));
+  assert!(c.equals(Tuple::color(0.75, 0.5, 0.25)));
+}
+#[test]
+fn a_gradient_linearly_interpolates_between_colors() {
+  let pattern = Gradient::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.25, 0.0, 0.0)).equals(Tuple::color(0.75, 0.75, 0.75)));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 0.0, 0.0)).equals(Tuple::color(0.5, 0.5, 0.5)));
+  assert!(pattern.pattern_at(Tuple::point(0.75, 0.0, 0.0)).equals(Tuple::color(0.25, 0.25, 0.25)));
+}
+#[test]
+fn a_ring_should_extend_in_both_x_and_z() {
+  let pattern = Ring::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(1.0, 0.0, 0.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 1.0)).equals(black()));
+  // 0.708 = just slightly more than sqrt(2)/2
+  assert!(pattern.pattern_at(Tuple::point(0.708, 0.0, 0.708)).equals(black()));
+}
+#[test]
+fn checkers_should_repeat_in_x() {
+  let pattern = Checker::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.99, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(1.01, 0.0, 0.0)).equals(black()));
+}
+#[test]
+fn checkers_should_repeat_in_y() {
+  let pattern = Checker::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.99, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 1.01, 0.0)).equals(black()));
+}
+#[test]
+fn checkers_should_repeat_in_z() {
+  let pattern = Checker::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.99)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 1.01)).equals(black()));
+}
+#[test]
+fn checkers_alternate_across_negative_coordinates() {
+  let pattern = Checker::new(white(), black());
+  assert!(pattern.pattern_at(Tuple::point(-0.5, 0.0, 0.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(-0.5, -0.5, 0.0)).equals(white()));
+}
+#[test]
+fn a_solid_pattern_is_the_same_everywhere() {
+  let pattern = Solid::new(Tuple::color(0.2, 0.4, 0.6));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(Tuple::color(0.2, 0.4, 0.6)));
+  assert!(pattern.pattern_at(Tuple::point(-3.5, 12.0, 0.25)).equals(Tuple::color(0.2, 0.4, 0.6)));
+}
+#[test]
+fn a_checker_of_nested_stripes() {
+  let red = Tuple::color(1.0, 0.0, 0.0);
+  let green = Tuple::color(0.0, 1.0, 0.0);
+  let blue = Tuple::color(0.0, 0.0, 1.0);
+  let a = Stripe::new(red, white());
+  let mut b = Stripe::new(green, blue);
+  b.set_transform(Matrix::scaling(0.5, 0.5, 0.5));
+  let pattern = Checker::with_patterns(Rc::new(a), Rc::new(b));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 0.0, 0.5)).equals(red));
+  // the inner pattern's own transform is applied on top of the checker's
+  assert!(pattern.pattern_at(Tuple::point(1.25, 0.0, 0.5)).equals(green));
+  assert!(pattern.pattern_at(Tuple::point(1.5, 0.0, 0.5)).equals(blue));
+}
+#[test]
+fn nested_patterns_follow_the_outer_transform() {
+  let mut pattern = Stripe::with_patterns(Rc::new(TestPattern::new()), Rc::new(Solid::new(black())));
+  pattern.set_transform(Matrix::translation(0.5, 0.0, 0.0));
+  let c = pattern.pattern_at_shape(&Sphere::new(), Tuple::point(1.0, 2.0, 3.0));
+  assert!(c.equals(Tuple::color(0.5, 2.0, 3.0)));
+}
+#[test]
+fn blending_averages_two_patterns() {
+  let stripes = Stripe::new(white(), black());
+  let pattern = Blend::new(Rc::new(stripes), Rc::new(Solid::new(black())));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 0.0, 0.0)).equals(Tuple::color(0.5, 0.5, 0.5)));
+  assert!(pattern.pattern_at(Tuple::point(1.5, 0.0, 0.0)).equals(black()));
+}
+#[test]
+fn blending_crossed_stripes() {
+  let a = Stripe::new(white(), black());
+  let mut b = Stripe::new(white(), black());
+  b.set_transform(Matrix::rotation_y(PI/2.0));
+  let pattern = Blend::new(Rc::new(a), Rc::new(b));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 0.0, -0.5)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 0.0, 0.5)).equals(Tuple::color(0.5, 0.5, 0.5)));
+  assert!(pattern.pattern_at(Tuple::point(1.5, 0.0, 0.5)).equals(black()));
+}
+#[test]
+fn perturbing_with_no_scale_leaves_the_pattern_alone() {
+  let pattern = Perturb::new(Rc::new(TestPattern::new()), 0.0);
+  let c = pattern.pattern_at(Tuple::point(0.3, 1.7, -2.2));
+  assert!(c.equals(Tuple::color(0.3, 1.7, -2.2)));
+}
+#[test]
+fn perturbing_moves_the_lookup_point_by_at_most_the_scale() {
+  let pattern = Perturb::new(Rc::new(TestPattern::new()), 0.2);
+  let mut moved = false;
+  for i in 0..100 {
+    let t = i as f64 * 0.173;
+    let p = Tuple::point(t, 0.5 - t, t * 2.0);
+    let c = pattern.pattern_at(p);
+    let offset = Tuple::vector(c.x - p.x, c.y - p.y, c.z - p.z);
+    assert!(offset.x.abs() <= 0.2 && offset.y.abs() <= 0.2 && offset.z.abs() <= 0.2);
+    moved |= offset.magnitude() > EPSILON;
+  }
+  assert!(moved);
+}
+#[test]
+fn marble_without_turbulence_is_smooth_bands() {
+  let mut pattern = Marble::new(white(), black());
+  pattern.turbulence = 0.0;
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(Tuple::color(0.5, 0.5, 0.5)));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 3.0, -2.0)).equals(black()));
+  assert!(pattern.pattern_at(Tuple::point(1.5, 0.0, 0.0)).equals(white()));
+}
+#[test]
+fn turbulence_bends_the_marble_bands() {
+  let smooth = {
+    let mut p = Marble::new(white(), black());
+    p.turbulence = 0.0;
+    p
+  };
+  let veined = Marble::new(white(), black());
+  let differs = (0..50).any(|i| {
+    let p = Tuple::point(i as f64 * 0.137, 0.3, i as f64 * 0.071);
+    !smooth.pattern_at(p).equals(veined.pattern_at(p))
+  });
+  assert!(differs);
+}
+#[test]
+fn wood_without_grain_is_concentric_rings() {
+  let mut pattern = Wood::new(white(), black());
+  pattern.grain = 0.0;
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.0, 0.0)).equals(white()));
+  assert!(pattern.pattern_at(Tuple::point(0.5, 7.0, 0.0)).equals(Tuple::color(0.5, 0.5, 0.5)));
+  assert!(pattern.pattern_at(Tuple::point(0.0, -2.0, 1.25)).equals(Tuple::color(0.75, 0.75, 0.75)));
+}
+#[test]
+fn clouds_stay_between_their_two_colors() {
+  let pattern = Clouds::new(white(), black());
+  for i in 0..100 {
+    let t = i as f64 * 0.219;
+    let c = pattern.pattern_at(Tuple::point(t, t * 0.5 - 2.0, 1.0 - t));
+    assert!((0.0..=1.0).contains(&c.x));
+    assert!(c.x == c.y && c.y == c.z);
+  }
+}
+#[test]
+fn noise_patterns_are_reproducible_and_follow_the_seed() {
+  let a = Clouds::new(white(), black());
+  let b = Clouds::new(white(), black());
+  let mut c = Clouds::new(white(), black());
+  c.noise = Perlin::with_seed(7);
+  let mut differs = false;
+  for i in 0..50 {
+    let p = Tuple::point(i as f64 * 0.173, 0.4, i as f64 * -0.091);
+    assert!(a.pattern_at(p).equals(b.pattern_at(p)));
+    differs |= !a.pattern_at(p).equals(c.pattern_at(p));
+  }
+  assert!(differs);
+}
+#[test]
+fn a_color_ramp_interpolates_between_stops() {
+  let red = Tuple::color(1.0, 0.0, 0.0);
+  let ramp = ColorRamp::new(vec![(1.0, white()), (0.0, black()), (0.5, red)]);
+  assert!(ramp.color_at(0.0).equals(black()));
+  assert!(ramp.color_at(0.25).equals(Tuple::color(0.5, 0.0, 0.0)));
+  assert!(ramp.color_at(0.5).equals(red));
+  assert!(ramp.color_at(0.75).equals(Tuple::color(1.0, 0.5, 0.5)));
+  assert!(ramp.color_at(1.0).equals(white()));
+}
+#[test]
+fn a_color_ramp_clamps_past_either_end() {
+  let ramp = ColorRamp::new(vec![(0.2, black()), (0.8, white())]);
+  assert!(ramp.color_at(-3.0).equals(black()));
+  assert!(ramp.color_at(0.1).equals(black()));
+  assert!(ramp.color_at(0.9).equals(white()));
+  assert!(ColorRamp::new(vec![(0.5, white())]).color_at(0.0).equals(white()));
+  assert!(ColorRamp::new(vec![]).color_at(0.5).equals(black()));
+}
+#[test]
+fn a_cellular_pattern_colors_by_distance() {
+  let pattern = Cellular::new(Feature::F1, ColorRamp::new(vec![(0.0, black()), (1.0, white())]));
+  for i in 0..50 {
+    let p = Tuple::point(i as f64 * 0.173, -0.4, i as f64 * 0.05);
+    let d = pattern.worley.value(p, Feature::F1);
+    let expected = if d >= 1.0 { white() } else { Tuple::color(d, d, d) };
+    assert!(pattern.pattern_at(p).equals(expected));
+  }
+}
+#[test]
+fn cell_borders_are_darkest_with_f2_minus_f1() {
+  let pattern = Cellular::new(Feature::F2MinusF1, ColorRamp::new(vec![(0.0, black()), (0.5, white())]));
+  // halfway between two feature points is exactly on the border between their cells
+  let mut border = None;
+  for i in 0..200 {
+    let p = Tuple::point(i as f64 * 0.05, 0.3, 0.7);
+    let (f1, f2) = pattern.worley.distances(p);
+    if f2 - f1 < 0.01 {
+      border = Some(p);
+      break;
+    }
+  }
+  let c = pattern.pattern_at(border.unwrap());
+  assert!(c.x < 0.02);
+}
@@ -0,0 +1,98 @@
+use crate::tuple::{Tuple, EPSILON};
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+
+// infinite xz plane through the origin
+#[derive(Clone)]
+pub struct Plane{ data: ShapeData }
+impl Plane {
+  pub fn new() -> Self {
+    Plane{data: ShapeData::new()}
+  }
+}
+impl Default for Plane {
+  fn default() -> Self {
+    Plane::new()
+  }
+}
+impl Shape for Plane {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    // a ray with no y slope is parallel to the plane or lies in it, either way it's invisible
+    if ray.direction.y.abs() < EPSILON {
+      return vec![];
+    }
+    let t = -ray.origin.y / ray.direction.y;
+    vec![Intersection::new(t, self)]
+  }
+  fn local_normal_at(&self, _point: Tuple) -> Tuple {
+    Tuple::vector(0.0, 1.0, 0.0)
+  }
+  fn bounds(&self) -> BoundingBox {
+    BoundingBox::new(
+      Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+      Tuple::point(f64::INFINITY, 0.0, f64::INFINITY)
+    )
+  }
+}
+
+#[test]
+fn the_normal_of_a_plane_is_constant_everywhere() {
+  let p = Plane::new();
+  let n1 = p.local_normal_at(Tuple::point(0.0, 0.0, 0.0));
+  let n2 = p.local_normal_at(Tuple::point(10.0, 0.0, -10.0));
+  let n3 = p.local_normal_at(Tuple::point(-5.0, 0.0, 150.0));
+  assert!(n1.equals(Tuple::vector(0.0, 1.0, 0.0)));
+  assert!(n2.equals(Tuple::vector(0.0, 1.0, 0.0)));
+  assert!(n3.equals(Tuple::vector(0.0, 1.0, 0.0)));
+}
+#[test]
+fn intersect_with_a_ray_parallel_to_the_plane() {
+  let p = Plane::new();
+  let r = Ray::new(Tuple::point(0.0, 10.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = p.local_intersect(&r);
+  assert!(xs.is_empty());
+}
+#[test]
+fn intersect_with_a_coplanar_ray() {
+  let p = Plane::new();
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = p.local_intersect(&r);
+  assert!(xs.is_empty());
+}
+#[test]
+fn a_ray_intersecting_a_plane_from_above() {
+  let p = Plane::new();
+  let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+  let xs = p.local_intersect(&r);
+  assert_eq!(xs.len(), 1);
+  assert_eq!(xs[0].t, 1.0);
+  assert!(std::ptr::addr_eq(xs[0].object, &p));
+}
+#[test]
+fn a_ray_intersecting_a_plane_from_below() {
+  let p = Plane::new();
+  let r = Ray::new(Tuple::point(0.0, -1.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+  let xs = p.local_intersect(&r);
+  assert_eq!(xs.len(), 1);
+  assert_eq!(xs[0].t, 1.0);
+  assert!(std::ptr::addr_eq(xs[0].object, &p));
+}
+#[test]
+fn a_plane_has_a_bounding_box() {
+  let p = Plane::new();
+  let b = p.bounds();
+  assert_eq!(b.min.x, f64::NEG_INFINITY);
+  assert_eq!(b.min.y, 0.0);
+  assert_eq!(b.min.z, f64::NEG_INFINITY);
+  assert_eq!(b.max.x, f64::INFINITY);
+  assert_eq!(b.max.y, 0.0);
+  assert_eq!(b.max.z, f64::INFINITY);
+}
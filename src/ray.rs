@@ -1,6 +1,8 @@
 use crate::tuple::Tuple;
+use crate::matrix::Matrix;
 
-pub struct Ray{ pub origin: Tuple, direction: Tuple }
+#[derive(Copy, Clone)]
+pub struct Ray{ pub origin: Tuple, pub direction: Tuple }
 impl Ray{
   pub fn new(origin: Tuple, direction: Tuple) -> Ray {
     Ray{origin: origin, direction: direction}
@@ -8,6 +10,9 @@ impl Ray{
   pub fn position(&self, t: f64) -> Tuple {
     self.origin + self.direction * t
   }
+  pub fn transform(&self, m: &Matrix) -> Ray {
+    Ray::new(m * self.origin, m * self.direction)
+  }
 } 
 
 #[test]
@@ -25,4 +30,20 @@ fn computing_a_point_from_a_distance() {
   assert!(r.position(1.0).equals(Tuple::point(3.0, 3.0, 4.0)));
   assert!(r.position(-1.0).equals(Tuple::point(1.0, 3.0, 4.0)));
   assert!(r.position(2.5).equals(Tuple::point(4.5, 3.0, 4.0)));
-}
\ No newline at end of file
+}
+#[test]
+fn translating_a_ray() {
+  let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+  let m = Matrix::translation(3.0, 4.0, 5.0);
+  let r2 = r.transform(&m);
+  assert!(r2.origin.equals(Tuple::point(4.0, 6.0, 8.0)));
+  assert!(r2.direction.equals(Tuple::vector(0.0, 1.0, 0.0)));
+}
+#[test]
+fn scaling_a_ray() {
+  let r = Ray::new(Tuple::point(1.0, 2.0, 3.0), Tuple::vector(0.0, 1.0, 0.0));
+  let m = Matrix::scaling(2.0, 3.0, 4.0);
+  let r2 = r.transform(&m);
+  assert!(r2.origin.equals(Tuple::point(2.0, 6.0, 12.0)));
+  assert!(r2.direction.equals(Tuple::vector(0.0, 3.0, 0.0)));
+}
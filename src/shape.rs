@@ -0,0 +1,217 @@
+use crate::tuple::Tuple;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::materials::Material;
+use crate::bounds::BoundingBox;
+use crate::intersection::{Intersection, Intersections};
+#[cfg(test)]
+use std::f64::consts::{PI, FRAC_1_SQRT_2};
+
+// state every shape carries, the Shape trait reaches it through data()
+// parents holds the inverse transform of each enclosing group, outermost first
+#[derive(Clone)]
+pub struct ShapeData{ transform: Matrix, inverse: Matrix, material: Material, parents: Vec<Matrix> }
+impl ShapeData {
+  pub fn new() -> Self {
+    ShapeData{transform: Matrix::identity(), inverse: Matrix::identity(), material: Material::new(), parents: vec![]}
+  }
+}
+impl Default for ShapeData {
+  fn default() -> Self {
+    ShapeData::new()
+  }
+}
+
+pub trait Shape {
+  fn data(&self) -> &ShapeData;
+  fn data_mut(&mut self) -> &mut ShapeData;
+  // the ray and point handed to these are already in object space
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>>;
+  fn local_normal_at(&self, point: Tuple) -> Tuple;
+  // object space extents
+  fn bounds(&self) -> BoundingBox;
+
+  fn transform(&self) -> &Matrix {
+    &self.data().transform
+  }
+  fn inverse(&self) -> &Matrix {
+    &self.data().inverse
+  }
+  fn set_transform(&mut self, transform: Matrix) {
+    let data = self.data_mut();
+    data.inverse = transform.inverse();
+    data.transform = transform;
+    self.propagate_parents();
+  }
+  fn parents(&self) -> &[Matrix] {
+    &self.data().parents
+  }
+  // called by the enclosing group whenever its own transform or chain changes
+  fn set_parents(&mut self, parents: Vec<Matrix>) {
+    self.data_mut().parents = parents;
+    self.propagate_parents();
+  }
+  // containers push their chain down to their children, everything else has nothing to do
+  fn propagate_parents(&mut self) {}
+  fn children(&self) -> &[Box<dyn Shape>] {
+    &[]
+  }
+  // extents as seen by whatever contains this shape
+  fn parent_space_bounds(&self) -> BoundingBox {
+    self.bounds().transform(self.transform())
+  }
+  // containers split themselves into sub-groups of at most roughly threshold children
+  fn divide(&mut self, _threshold: usize) {}
+  // containers index their children with a flattened surface area heuristic tree
+  fn build_bvh(&mut self) {}
+  // is other this shape or anywhere beneath it
+  fn includes(&self, other: &dyn Shape) -> bool {
+    std::ptr::addr_eq(self as *const Self, other as *const dyn Shape)
+      || self.children().iter().any(|child| child.includes(other))
+  }
+  fn material(&self) -> &Material {
+    &self.data().material
+  }
+  fn set_material(&mut self, material: Material) {
+    self.data_mut().material = material;
+  }
+  fn intersect(&self, world_ray: &Ray) -> Intersections<'_> {
+    let ray = world_ray.transform(self.inverse());
+    Intersections::new(self.local_intersect(&ray))
+  }
+  // shapes that interpolate their normal across the surface read u/v off of the hit
+  fn local_normal_at_hit(&self, point: Tuple, _hit: &Intersection) -> Tuple {
+    self.local_normal_at(point)
+  }
+  fn normal_at(&self, world_point: Tuple) -> Tuple {
+    let local_point = self.world_to_object(world_point);
+    self.normal_to_world(self.local_normal_at(local_point))
+  }
+  fn normal_at_hit(&self, world_point: Tuple, hit: &Intersection) -> Tuple {
+    let local_point = self.world_to_object(world_point);
+    self.normal_to_world(self.local_normal_at_hit(local_point, hit))
+  }
+  // walks down from the outermost group to this shape's own object space
+  fn world_to_object(&self, world_point: Tuple) -> Tuple {
+    let mut point = world_point;
+    for parent_inverse in self.parents() {
+      point = parent_inverse * point;
+    }
+    self.inverse() * point
+  }
+  // walks back up from this shape to world space
+  fn normal_to_world(&self, local_normal: Tuple) -> Tuple {
+    let mut normal = local_normal;
+    for inverse in std::iter::once(self.inverse()).chain(self.parents().iter().rev()) {
+      // the inverse transpose keeps normals perpendicular under non-uniform scaling,
+      // but it also drags the translation into w, which has to be thrown away
+      normal = &inverse.transpose() * normal;
+      normal.w = 0.0;
+      normal = normal.normalize();
+    }
+    normal
+  }
+}
+
+#[cfg(test)]
+use std::cell::RefCell;
+#[cfg(test)]
+use std::rc::Rc;
+
+// records the object space ray so the trait's default methods can be checked,
+// shared so a test can still look at it once the shape is boxed up in a group
+#[cfg(test)]
+pub struct TestShape{ data: ShapeData, pub saved_ray: Rc<RefCell<Option<Ray>>> }
+#[cfg(test)]
+impl TestShape {
+  pub fn new() -> Self {
+    TestShape{data: ShapeData::new(), saved_ray: Rc::new(RefCell::new(None))}
+  }
+}
+#[cfg(test)]
+impl Shape for TestShape {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    *self.saved_ray.borrow_mut() = Some(*ray);
+    vec![]
+  }
+  fn local_normal_at(&self, point: Tuple) -> Tuple {
+    Tuple::vector(point.x, point.y, point.z)
+  }
+  fn bounds(&self) -> BoundingBox {
+    BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+  }
+}
+
+#[test]
+fn the_default_transformation() {
+  let s = TestShape::new();
+  assert!(s.transform().equals(Matrix::identity()));
+}
+#[test]
+fn assigning_a_transformation() {
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::translation(2.0, 3.0, 4.0));
+  assert!(s.transform().equals(Matrix::translation(2.0, 3.0, 4.0)));
+  assert!(s.inverse().equals(Matrix::translation(-2.0, -3.0, -4.0)));
+}
+#[test]
+fn the_default_material() {
+  let s = TestShape::new();
+  assert_eq!(s.material().ambient, Material::new().ambient);
+}
+#[test]
+fn assigning_a_material() {
+  let mut s = TestShape::new();
+  let mut m = Material::new();
+  m.ambient = 1.0;
+  s.set_material(m);
+  assert_eq!(s.material().ambient, 1.0);
+}
+#[test]
+fn intersecting_a_scaled_shape_with_a_ray() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  s.intersect(&r);
+  let saved = s.saved_ray.borrow().unwrap();
+  assert!(saved.origin.equals(Tuple::point(0.0, 0.0, -2.5)));
+  assert!(saved.direction.equals(Tuple::vector(0.0, 0.0, 0.5)));
+}
+#[test]
+fn intersecting_a_translated_shape_with_a_ray() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  s.intersect(&r);
+  let saved = s.saved_ray.borrow().unwrap();
+  assert!(saved.origin.equals(Tuple::point(-5.0, 0.0, -5.0)));
+  assert!(saved.direction.equals(Tuple::vector(0.0, 0.0, 1.0)));
+}
+#[test]
+fn computing_the_normal_on_a_translated_shape() {
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::translation(0.0, 1.0, 0.0));
+  let n = s.normal_at(Tuple::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+  assert!(n.equals(Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)));
+}
+#[test]
+fn computing_the_normal_on_a_transformed_shape() {
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(PI/5.0));
+  let n = s.normal_at(Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+  assert!(n.equals(Tuple::vector(0.0, 0.97014, -0.24254)));
+}
+#[test]
+fn querying_a_shapes_bounding_box_in_its_parents_space() {
+  let mut s = TestShape::new();
+  s.set_transform(Matrix::translation(1.0, -3.0, 5.0) * Matrix::scaling(0.5, 2.0, 4.0));
+  let b = s.parent_space_bounds();
+  assert!(b.min.equals(Tuple::point(0.5, -5.0, 1.0)));
+  assert!(b.max.equals(Tuple::point(1.5, -1.0, 9.0)));
+}
@@ -0,0 +1,206 @@
+use crate::tuple::Tuple;
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use std::f64::consts::{PI, FRAC_1_SQRT_2};
+#[cfg(test)]
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+
+// unit sphere centered on the origin
+#[derive(Clone)]
+pub struct Sphere{ data: ShapeData }
+impl Sphere {
+  pub fn new() -> Self {
+    Sphere{data: ShapeData::new()}
+  }
+}
+impl Default for Sphere {
+  fn default() -> Self {
+    Sphere::new()
+  }
+}
+// fully transparent sphere with the refractive index of glass
+#[cfg(test)]
+pub fn glass_sphere() -> Sphere {
+  let mut s = Sphere::new();
+  let mut m = Material::new();
+  m.transparency = 1.0;
+  m.refractive_index = 1.5;
+  s.set_material(m);
+  s
+}
+
+impl Shape for Sphere {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    let sphere_to_ray = ray.origin - Tuple::point(0.0, 0.0, 0.0);
+    let a = ray.direction.dot(ray.direction);
+    let b = 2.0 * ray.direction.dot(sphere_to_ray);
+    let c = sphere_to_ray.dot(sphere_to_ray) - 1.0;
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+      return vec![];
+    }
+    let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
+    let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
+    vec![Intersection::new(t1, self), Intersection::new(t2, self)]
+  }
+  fn local_normal_at(&self, point: Tuple) -> Tuple {
+    point - Tuple::point(0.0, 0.0, 0.0)
+  }
+  fn bounds(&self) -> BoundingBox {
+    BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+  }
+}
+
+#[test]
+fn a_ray_intersects_a_sphere_at_two_points() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 4.0);
+  assert_eq!(xs[1].t, 6.0);
+}
+#[test]
+fn a_ray_intersects_a_sphere_at_a_tangent() {
+  let r = Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 5.0);
+  assert_eq!(xs[1].t, 5.0);
+}
+#[test]
+fn a_ray_misses_a_sphere() {
+  let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert!(xs.is_empty());
+}
+#[test]
+fn a_ray_originates_inside_a_sphere() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, -1.0);
+  assert_eq!(xs[1].t, 1.0);
+}
+#[test]
+fn a_sphere_is_behind_a_ray() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, -6.0);
+  assert_eq!(xs[1].t, -4.0);
+}
+#[test]
+fn intersect_sets_the_object_on_the_intersection() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let s = Sphere::new();
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert!(std::ptr::addr_eq(xs[0].object, &s));
+  assert!(std::ptr::addr_eq(xs[1].object, &s));
+}
+#[test]
+fn a_spheres_default_transformation() {
+  let s = Sphere::new();
+  assert!(s.transform().equals(Matrix::identity()));
+}
+#[test]
+fn changing_a_spheres_transformation() {
+  let mut s = Sphere::new();
+  let t = Matrix::translation(2.0, 3.0, 4.0);
+  s.set_transform(t.clone());
+  assert!(s.transform().equals(t));
+}
+#[test]
+fn intersecting_a_scaled_sphere_with_a_ray() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 2);
+  assert_eq!(xs[0].t, 3.0);
+  assert_eq!(xs[1].t, 7.0);
+}
+#[test]
+fn intersecting_a_translated_sphere_with_a_ray() {
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(5.0, 0.0, 0.0));
+  let xs = s.intersect(&r);
+  assert_eq!(xs.len(), 0);
+}
+#[test]
+fn the_normal_on_a_sphere_at_a_point_on_the_x_axis() {
+  let s = Sphere::new();
+  let n = s.normal_at(Tuple::point(1.0, 0.0, 0.0));
+  assert!(n.equals(Tuple::vector(1.0, 0.0, 0.0)));
+}
+#[test]
+fn the_normal_on_a_sphere_at_a_point_on_the_y_axis() {
+  let s = Sphere::new();
+  let n = s.normal_at(Tuple::point(0.0, 1.0, 0.0));
+  assert!(n.equals(Tuple::vector(0.0, 1.0, 0.0)));
+}
+#[test]
+fn the_normal_on_a_sphere_at_a_point_on_the_z_axis() {
+  let s = Sphere::new();
+  let n = s.normal_at(Tuple::point(0.0, 0.0, 1.0));
+  assert!(n.equals(Tuple::vector(0.0, 0.0, 1.0)));
+}
+#[test]
+fn the_normal_on_a_sphere_at_a_nonaxial_point() {
+  let s = Sphere::new();
+  let v = f64::sqrt(3.0) / 3.0;
+  let n = s.normal_at(Tuple::point(v, v, v));
+  assert!(n.equals(Tuple::vector(v, v, v)));
+}
+#[test]
+fn the_normal_is_a_normalized_vector() {
+  let s = Sphere::new();
+  let v = f64::sqrt(3.0) / 3.0;
+  let n = s.normal_at(Tuple::point(v, v, v));
+  assert!(n.equals(n.normalize()));
+}
+#[test]
+fn computing_the_normal_on_a_translated_sphere() {
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::translation(0.0, 1.0, 0.0));
+  let n = s.normal_at(Tuple::point(0.0, 1.0 + FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+  assert!(n.equals(Tuple::vector(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2)));
+}
+#[test]
+fn computing_the_normal_on_a_nonuniformly_scaled_sphere() {
+  let mut s = Sphere::new();
+  s.set_transform(Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(PI/5.0));
+  let n = s.normal_at(Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+  assert!(n.equals(Tuple::vector(0.0, 0.97014, -0.24254)));
+}
+#[test]
+fn a_sphere_has_a_bounding_box() {
+  let s = Sphere::new();
+  let b = s.bounds();
+  assert!(b.min.equals(Tuple::point(-1.0, -1.0, -1.0)));
+  assert!(b.max.equals(Tuple::point(1.0, 1.0, 1.0)));
+}
+#[test]
+fn a_helper_for_producing_a_sphere_with_a_glassy_material() {
+  let s = glass_sphere();
+  assert!(s.transform().equals(Matrix::identity()));
+  assert_eq!(s.material().transparency, 1.0);
+  assert_eq!(s.material().refractive_index, 1.5);
+}
@@ -144,3 +144,40 @@ fn fluent_chaining_of_transformations() {
     .translate(10.0, 5.0, 7.0);
   assert!((t * p).equals(Tuple::point(15.0, 0.0, 7.0)));
 }
+#[test]
+fn the_transformation_matrix_for_the_default_orientation() {
+  let from = Tuple::point(0.0, 0.0, 0.0);
+  let to = Tuple::point(0.0, 0.0, -1.0);
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+  let t = Matrix::view_transform(from, to, up);
+  assert!(t.equals(Matrix::identity()));
+}
+#[test]
+fn a_view_transformation_matrix_looking_in_positive_z_direction() {
+  let from = Tuple::point(0.0, 0.0, 0.0);
+  let to = Tuple::point(0.0, 0.0, 1.0);
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+  let t = Matrix::view_transform(from, to, up);
+  assert!(t.equals(Matrix::scaling(-1.0, 1.0, -1.0)));
+}
+#[test]
+fn the_view_transformation_moves_the_world() {
+  let from = Tuple::point(0.0, 0.0, 8.0);
+  let to = Tuple::point(0.0, 0.0, 0.0);
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+  let t = Matrix::view_transform(from, to, up);
+  assert!(t.equals(Matrix::translation(0.0, 0.0, -8.0)));
+}
+#[test]
+fn an_arbitrary_view_transformation() {
+  let from = Tuple::point(1.0, 3.0, 2.0);
+  let to = Tuple::point(4.0, -2.0, 8.0);
+  let up = Tuple::vector(1.0, 1.0, 0.0);
+  let t = Matrix::view_transform(from, to, up);
+  assert!(t.equals(Matrix{m: vec![
+    vec![-0.50709, 0.50709, 0.67612, -2.36643],
+    vec![0.76772, 0.60609, 0.12122, -2.82843],
+    vec![-0.35857, 0.59761, -0.71714, 0.00000],
+    vec![0.00000, 0.00000, 0.00000, 1.00000]
+  ]}));
+}
@@ -0,0 +1,237 @@
+use crate::tuple::{Tuple, EPSILON};
+use crate::ray::Ray;
+use crate::shape::{Shape, ShapeData};
+use crate::intersection::Intersection;
+use crate::bounds::BoundingBox;
+#[cfg(test)]
+use crate::intersection::Intersections;
+
+fn triangle_bounds(p1: Tuple, p2: Tuple, p3: Tuple) -> BoundingBox {
+  let mut b = BoundingBox::empty();
+  b.add_point(p1);
+  b.add_point(p2);
+  b.add_point(p3);
+  b
+}
+
+// möller-trumbore, returns t along with the u/v barycentric coordinates of the hit
+fn intersect_triangle(p1: Tuple, e1: Tuple, e2: Tuple, ray: &Ray) -> Option<(f64, f64, f64)> {
+  let dir_cross_e2 = ray.direction.cross(e2);
+  let det = e1.dot(dir_cross_e2);
+  // the ray is parallel to the triangle
+  if det.abs() < EPSILON {
+    return None;
+  }
+  let f = 1.0 / det;
+  let p1_to_origin = ray.origin - p1;
+  let u = f * p1_to_origin.dot(dir_cross_e2);
+  if !(0.0..=1.0).contains(&u) {
+    return None;
+  }
+  let origin_cross_e1 = p1_to_origin.cross(e1);
+  let v = f * ray.direction.dot(origin_cross_e1);
+  if v < 0.0 || (u + v) > 1.0 {
+    return None;
+  }
+  let t = f * e2.dot(origin_cross_e1);
+  Some((t, u, v))
+}
+
+#[derive(Clone)]
+pub struct Triangle{
+  data: ShapeData,
+  pub p1: Tuple,
+  pub p2: Tuple,
+  pub p3: Tuple,
+  pub e1: Tuple,
+  pub e2: Tuple,
+  pub normal: Tuple
+}
+impl Triangle {
+  pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+    let e1 = p2 - p1;
+    let e2 = p3 - p1;
+    let normal = e2.cross(e1).normalize();
+    Triangle{data: ShapeData::new(), p1, p2, p3, e1, e2, normal}
+  }
+}
+impl Shape for Triangle {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    match intersect_triangle(self.p1, self.e1, self.e2, ray) {
+      Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+      None => vec![]
+    }
+  }
+  fn local_normal_at(&self, _point: Tuple) -> Tuple {
+    self.normal
+  }
+  fn bounds(&self) -> BoundingBox {
+    triangle_bounds(self.p1, self.p2, self.p3)
+  }
+}
+
+// a triangle with a normal at each corner, blended across the face so meshes shade smoothly
+#[derive(Clone)]
+pub struct SmoothTriangle{
+  data: ShapeData,
+  pub p1: Tuple,
+  pub p2: Tuple,
+  pub p3: Tuple,
+  pub n1: Tuple,
+  pub n2: Tuple,
+  pub n3: Tuple,
+  pub e1: Tuple,
+  pub e2: Tuple,
+  pub normal: Tuple
+}
+impl SmoothTriangle {
+  pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+    let e1 = p2 - p1;
+    let e2 = p3 - p1;
+    let normal = e2.cross(e1).normalize();
+    SmoothTriangle{data: ShapeData::new(), p1, p2, p3, n1, n2, n3, e1, e2, normal}
+  }
+}
+impl Shape for SmoothTriangle {
+  fn data(&self) -> &ShapeData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut ShapeData {
+    &mut self.data
+  }
+  fn local_intersect(&self, ray: &Ray) -> Vec<Intersection<'_>> {
+    match intersect_triangle(self.p1, self.e1, self.e2, ray) {
+      Some((t, u, v)) => vec![Intersection::with_uv(t, self, u, v)],
+      None => vec![]
+    }
+  }
+  // without a hit to interpolate from, fall back to the face normal
+  fn local_normal_at(&self, _point: Tuple) -> Tuple {
+    self.normal
+  }
+  fn local_normal_at_hit(&self, _point: Tuple, hit: &Intersection) -> Tuple {
+    self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v)
+  }
+  fn bounds(&self) -> BoundingBox {
+    triangle_bounds(self.p1, self.p2, self.p3)
+  }
+}
+
+#[test]
+fn constructing_a_triangle() {
+  let p1 = Tuple::point(0.0, 1.0, 0.0);
+  let p2 = Tuple::point(-1.0, 0.0, 0.0);
+  let p3 = Tuple::point(1.0, 0.0, 0.0);
+  let t = Triangle::new(p1, p2, p3);
+  assert!(t.p1.equals(p1));
+  assert!(t.p2.equals(p2));
+  assert!(t.p3.equals(p3));
+  assert!(t.e1.equals(Tuple::vector(-1.0, -1.0, 0.0)));
+  assert!(t.e2.equals(Tuple::vector(1.0, -1.0, 0.0)));
+  assert!(t.normal.equals(Tuple::vector(0.0, 0.0, -1.0)));
+}
+#[test]
+fn finding_the_normal_on_a_triangle() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  assert!(t.local_normal_at(Tuple::point(0.0, 0.5, 0.0)).equals(t.normal));
+  assert!(t.local_normal_at(Tuple::point(-0.5, 0.75, 0.0)).equals(t.normal));
+  assert!(t.local_normal_at(Tuple::point(0.5, 0.25, 0.0)).equals(t.normal));
+}
+#[test]
+fn intersecting_a_ray_parallel_to_the_triangle() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0));
+  assert!(t.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_misses_the_p1_p3_edge() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let r = Ray::new(Tuple::point(1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert!(t.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_misses_the_p1_p2_edge() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let r = Ray::new(Tuple::point(-1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert!(t.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_misses_the_p2_p3_edge() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let r = Ray::new(Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  assert!(t.local_intersect(&r).is_empty());
+}
+#[test]
+fn a_ray_strikes_a_triangle() {
+  let t = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = t.local_intersect(&r);
+  assert_eq!(xs.len(), 1);
+  assert_eq!(xs[0].t, 2.0);
+}
+
+#[cfg(test)]
+fn test_smooth_triangle() -> SmoothTriangle {
+  SmoothTriangle::new(
+    Tuple::point(0.0, 1.0, 0.0),
+    Tuple::point(-1.0, 0.0, 0.0),
+    Tuple::point(1.0, 0.0, 0.0),
+    Tuple::vector(0.0, 1.0, 0.0),
+    Tuple::vector(-1.0, 0.0, 0.0),
+    Tuple::vector(1.0, 0.0, 0.0)
+  )
+}
+#[test]
+fn constructing_a_smooth_triangle() {
+  let tri = test_smooth_triangle();
+  assert!(tri.p1.equals(Tuple::point(0.0, 1.0, 0.0)));
+  assert!(tri.p2.equals(Tuple::point(-1.0, 0.0, 0.0)));
+  assert!(tri.p3.equals(Tuple::point(1.0, 0.0, 0.0)));
+  assert!(tri.n1.equals(Tuple::vector(0.0, 1.0, 0.0)));
+  assert!(tri.n2.equals(Tuple::vector(-1.0, 0.0, 0.0)));
+  assert!(tri.n3.equals(Tuple::vector(1.0, 0.0, 0.0)));
+}
+#[test]
+fn an_intersection_can_encapsulate_u_and_v() {
+  let s = Triangle::new(Tuple::point(0.0, 1.0, 0.0), Tuple::point(-1.0, 0.0, 0.0), Tuple::point(1.0, 0.0, 0.0));
+  let i = Intersection::with_uv(3.5, &s, 0.2, 0.4);
+  assert_eq!(i.u, 0.2);
+  assert_eq!(i.v, 0.4);
+}
+#[test]
+fn an_intersection_with_a_smooth_triangle_stores_u_and_v() {
+  let tri = test_smooth_triangle();
+  let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = tri.local_intersect(&r);
+  assert!((xs[0].u - 0.45).abs() < EPSILON);
+  assert!((xs[0].v - 0.25).abs() < EPSILON);
+}
+#[test]
+fn a_smooth_triangle_uses_u_and_v_to_interpolate_the_normal() {
+  let tri = test_smooth_triangle();
+  let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+  let n = tri.normal_at_hit(Tuple::point(0.0, 0.0, 0.0), &i);
+  assert!(n.equals(Tuple::vector(-0.5547, 0.83205, 0.0)));
+}
+#[test]
+fn preparing_the_normal_on_a_smooth_triangle() {
+  let tri = test_smooth_triangle();
+  let i = Intersection::with_uv(1.0, &tri, 0.45, 0.25);
+  let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = Intersections::new(vec![i]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  assert!(comps.normalv.equals(Tuple::vector(-0.5547, 0.83205, 0.0)));
+}
+#[test]
+fn a_triangle_has_a_bounding_box() {
+  let t = Triangle::new(Tuple::point(-3.0, 7.0, 2.0), Tuple::point(6.0, 2.0, -4.0), Tuple::point(2.0, -1.0, -1.0));
+  let b = t.bounds();
+  assert!(b.min.equals(Tuple::point(-3.0, -1.0, -4.0)));
+  assert!(b.max.equals(Tuple::point(6.0, 7.0, 2.0)));
+}
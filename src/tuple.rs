@@ -1,4 +1,9 @@
 use std::ops;
+#[cfg(test)]
+use std::f64::consts::FRAC_1_SQRT_2;
+
+// tolerance for float comparisons and for nudging points off of surfaces
+pub const EPSILON: f64 = 0.00001;
 
 #[derive(Copy, Clone)]
 pub struct Tuple{ pub x: f64, pub y: f64, pub z: f64, pub w: f64 }
@@ -14,7 +19,6 @@ impl Tuple {
     self.w == 0.0
   }
   pub fn equals(&self, t: Self) -> bool {
-    const EPSILON: f64 = 0.00001;
     (self.x - t.x).abs() < EPSILON && (self.y - t.y).abs() < EPSILON && (self.z - t.z).abs() < EPSILON && (self.w - t.w).abs() < EPSILON
   }
   pub fn magnitude(&self) -> f64 {
@@ -35,6 +39,10 @@ impl Tuple {
       0.0 // vector
     )
   }
+  // bounce a vector off a surface with the given normal
+  pub fn reflect(&self, normal: Self) -> Self {
+    *self - normal * 2.0 * self.dot(normal)
+  }
   pub fn red(&self) -> f64 {
     self.x
   }
@@ -83,6 +91,13 @@ impl ops::Mul<f64> for Tuple {
     Tuple::new(self.x * _rhs, self.y * _rhs, self.z * _rhs, self.w * _rhs)
   }
 }
+// hadamard product, used to blend colors
+impl ops::Mul<Tuple> for Tuple {
+  type Output = Self;
+  fn mul(self, _rhs: Tuple) -> Self::Output {
+    Tuple::new(self.x * _rhs.x, self.y * _rhs.y, self.z * _rhs.z, self.w * _rhs.w)
+  }
+}
 impl ops::Div<f64> for Tuple {
   type Output = Self;
   fn div(self, _rhs: f64) -> Self::Output {
@@ -246,3 +261,21 @@ fn multipyling_a_color_by_a_scalar() {
   let c = Tuple::color(0.2, 0.3, 0.4);
   assert!((c * 2.0).equals(Tuple::color(0.4, 0.6, 0.8)));
 }
+#[test]
+fn multiplying_colors() {
+  let c1 = Tuple::color(1.0, 0.2, 0.4);
+  let c2 = Tuple::color(0.9, 1.0, 0.1);
+  assert!((c1 * c2).equals(Tuple::color(0.9, 0.2, 0.04)));
+}
+#[test]
+fn reflecting_a_vector_approaching_at_45_degrees() {
+  let v = Tuple::vector(1.0, -1.0, 0.0);
+  let n = Tuple::vector(0.0, 1.0, 0.0);
+  assert!(v.reflect(n).equals(Tuple::vector(1.0, 1.0, 0.0)));
+}
+#[test]
+fn reflecting_a_vector_off_a_slanted_surface() {
+  let v = Tuple::vector(0.0, -1.0, 0.0);
+  let n = Tuple::vector(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0);
+  assert!(v.reflect(n).equals(Tuple::vector(1.0, 0.0, 0.0)));
+}
@@ -0,0 +1,432 @@
+use std::rc::Rc;
+use std::f64::consts::PI;
+#[cfg(test)]
+use std::f64::consts::FRAC_1_SQRT_2;
+use crate::tuple::Tuple;
+use crate::patterns::{Pattern, PatternData};
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use crate::shape::Shape;
+
+// a flat pattern over the unit square, wrapped onto a 3d surface by one of the mappings below
+pub trait UvPattern {
+  fn uv_pattern_at(&self, u: f64, v: f64) -> Tuple;
+}
+
+// width by height squares alternating between a and b
+pub struct UvCheckers{ pub width: f64, pub height: f64, pub a: Tuple, pub b: Tuple }
+impl UvCheckers {
+  pub fn new(width: f64, height: f64, a: Tuple, b: Tuple) -> Self {
+    UvCheckers{width, height, a, b}
+  }
+}
+impl UvPattern for UvCheckers {
+  fn uv_pattern_at(&self, u: f64, v: f64) -> Tuple {
+    let u2 = (u * self.width).floor();
+    let v2 = (v * self.height).floor();
+    if (u2 + v2) % 2.0 == 0.0 {
+      self.a
+    }
+    else {
+      self.b
+    }
+  }
+}
+
+// one color over the face with a different one in each corner, for checking
+// that a mapping puts the texture the right way up and the right way round
+pub struct UvAlignCheck{ pub main: Tuple, pub ul: Tuple, pub ur: Tuple, pub bl: Tuple, pub br: Tuple }
+impl UvAlignCheck {
+  pub fn new(main: Tuple, ul: Tuple, ur: Tuple, bl: Tuple, br: Tuple) -> Self {
+    UvAlignCheck{main, ul, ur, bl, br}
+  }
+}
+impl UvPattern for UvAlignCheck {
+  fn uv_pattern_at(&self, u: f64, v: f64) -> Tuple {
+    if v > 0.8 {
+      if u < 0.2 {
+        return self.ul;
+      }
+      if u > 0.8 {
+        return self.ur;
+      }
+    }
+    else if v < 0.2 {
+      if u < 0.2 {
+        return self.bl;
+      }
+      if u > 0.8 {
+        return self.br;
+      }
+    }
+    self.main
+  }
+}
+
+// longitude and latitude on the unit sphere, u runs west to east and v south to north
+pub fn spherical_map(point: Tuple) -> (f64, f64) {
+  let theta = point.x.atan2(point.z);
+  let radius = Tuple::vector(point.x, point.y, point.z).magnitude();
+  let phi = (point.y / radius).acos();
+  let raw_u = theta / (2.0 * PI);
+  // raw_u runs -0.5..0.5 the wrong way round
+  let u = 1.0 - (raw_u + 0.5);
+  let v = 1.0 - phi / PI;
+  (u, v)
+}
+
+// x and z tiled every unit, y is ignored
+pub fn planar_map(point: Tuple) -> (f64, f64) {
+  (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+// around the y axis for u, and y tiled every unit for v
+pub fn cylindrical_map(point: Tuple) -> (f64, f64) {
+  let theta = point.x.atan2(point.z);
+  let raw_u = theta / (2.0 * PI);
+  let u = 1.0 - (raw_u + 0.5);
+  (u, point.y.rem_euclid(1.0))
+}
+
+// the mappings a TextureMap can use
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Mapping {
+  Spherical,
+  Planar,
+  Cylindrical
+}
+impl Mapping {
+  pub fn uv(&self, point: Tuple) -> (f64, f64) {
+    match self {
+      Mapping::Spherical => spherical_map(point),
+      Mapping::Planar => planar_map(point),
+      Mapping::Cylindrical => cylindrical_map(point)
+    }
+  }
+}
+
+// the six faces of the -1..1 cube, named as seen from the front (looking down -z)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Face {
+  Left,
+  Right,
+  Front,
+  Back,
+  Up,
+  Down
+}
+
+// whichever axis the point is furthest along picks the face
+pub fn face_from_point(point: Tuple) -> Face {
+  let coord = point.x.abs().max(point.y.abs()).max(point.z.abs());
+  if coord == point.x {
+    Face::Right
+  }
+  else if coord == -point.x {
+    Face::Left
+  }
+  else if coord == point.y {
+    Face::Up
+  }
+  else if coord == -point.y {
+    Face::Down
+  }
+  else if coord == point.z {
+    Face::Front
+  }
+  else {
+    Face::Back
+  }
+}
+
+// u and v on one face of the cube, each face laid out as if unfolded flat around the front
+pub fn cube_map(face: Face, point: Tuple) -> (f64, f64) {
+  let (u, v) = match face {
+    Face::Front => (point.x + 1.0, point.y + 1.0),
+    Face::Back => (1.0 - point.x, point.y + 1.0),
+    Face::Left => (point.z + 1.0, point.y + 1.0),
+    Face::Right => (1.0 - point.z, point.y + 1.0),
+    Face::Up => (point.x + 1.0, 1.0 - point.z),
+    Face::Down => (point.x + 1.0, point.z + 1.0)
+  };
+  (u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
+}
+
+// glues a uv pattern onto a surface through one of the mappings
+pub struct TextureMap{ data: PatternData, pub uv_pattern: Rc<dyn UvPattern>, pub mapping: Mapping }
+impl TextureMap {
+  pub fn new(uv_pattern: Rc<dyn UvPattern>, mapping: Mapping) -> Self {
+    TextureMap{data: PatternData::new(), uv_pattern, mapping}
+  }
+}
+impl Pattern for TextureMap {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let (u, v) = self.mapping.uv(point);
+    self.uv_pattern.uv_pattern_at(u, v)
+  }
+}
+
+// a separate uv pattern on each face of the cube
+pub struct CubeMap{
+  data: PatternData,
+  pub left: Rc<dyn UvPattern>,
+  pub front: Rc<dyn UvPattern>,
+  pub right: Rc<dyn UvPattern>,
+  pub back: Rc<dyn UvPattern>,
+  pub up: Rc<dyn UvPattern>,
+  pub down: Rc<dyn UvPattern>
+}
+impl CubeMap {
+  pub fn new(left: Rc<dyn UvPattern>, front: Rc<dyn UvPattern>, right: Rc<dyn UvPattern>, back: Rc<dyn UvPattern>, up: Rc<dyn UvPattern>, down: Rc<dyn UvPattern>) -> Self {
+    CubeMap{data: PatternData::new(), left, front, right, back, up, down}
+  }
+}
+impl Pattern for CubeMap {
+  fn data(&self) -> &PatternData {
+    &self.data
+  }
+  fn data_mut(&mut self) -> &mut PatternData {
+    &mut self.data
+  }
+  fn pattern_at(&self, point: Tuple) -> Tuple {
+    let face = face_from_point(point);
+    let pattern = match face {
+      Face::Left => &self.left,
+      Face::Front => &self.front,
+      Face::Right => &self.right,
+      Face::Back => &self.back,
+      Face::Up => &self.up,
+      Face::Down => &self.down
+    };
+    let (u, v) = cube_map(face, point);
+    pattern.uv_pattern_at(u, v)
+  }
+}
+
+#[cfg(test)]
+fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+  assert!((actual.0 - expected.0).abs() < 0.0001, "u was {} not {}", actual.0, expected.0);
+  assert!((actual.1 - expected.1).abs() < 0.0001, "v was {} not {}", actual.1, expected.1);
+}
+
+#[test]
+fn checker_pattern_in_2d() {
+  let black = Tuple::color(0.0, 0.0, 0.0);
+  let white = Tuple::color(1.0, 1.0, 1.0);
+  let checkers = UvCheckers::new(2.0, 2.0, black, white);
+  assert!(checkers.uv_pattern_at(0.0, 0.0).equals(black));
+  assert!(checkers.uv_pattern_at(0.5, 0.0).equals(white));
+  assert!(checkers.uv_pattern_at(0.0, 0.5).equals(white));
+  assert!(checkers.uv_pattern_at(0.5, 0.5).equals(black));
+  assert!(checkers.uv_pattern_at(1.0, 1.0).equals(black));
+}
+#[test]
+fn using_a_spherical_mapping_on_a_3d_point() {
+  let v = FRAC_1_SQRT_2;
+  let examples = [
+    (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.5)),
+    (Tuple::point(1.0, 0.0, 0.0), (0.25, 0.5)),
+    (Tuple::point(0.0, 0.0, 1.0), (0.5, 0.5)),
+    (Tuple::point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+    (Tuple::point(0.0, 1.0, 0.0), (0.5, 1.0)),
+    (Tuple::point(0.0, -1.0, 0.0), (0.5, 0.0)),
+    (Tuple::point(v, v, 0.0), (0.25, 0.75))
+  ];
+  for (point, uv) in examples {
+    assert_uv(spherical_map(point), uv);
+  }
+}
+#[test]
+fn using_a_texture_map_pattern_with_a_spherical_map() {
+  let black = Tuple::color(0.0, 0.0, 0.0);
+  let white = Tuple::color(1.0, 1.0, 1.0);
+  let checkers = UvCheckers::new(16.0, 8.0, black, white);
+  let pattern = TextureMap::new(Rc::new(checkers), Mapping::Spherical);
+  let examples = [
+    (Tuple::point(0.4315, 0.4670, 0.7719), white),
+    (Tuple::point(-0.9654, 0.2552, -0.0534), black),
+    (Tuple::point(0.1039, 0.7090, 0.6975), white),
+    (Tuple::point(-0.4986, -0.7856, -0.3663), black),
+    (Tuple::point(-0.0317, -0.9395, 0.3411), black),
+    (Tuple::point(0.4809, -0.7721, 0.4154), black),
+    (Tuple::point(0.0285, -0.9612, -0.2745), black),
+    (Tuple::point(-0.5734, -0.2162, -0.7903), white),
+    (Tuple::point(0.7688, -0.1470, 0.6223), black),
+    (Tuple::point(-0.7652, 0.2175, 0.6060), black)
+  ];
+  for (point, color) in examples {
+    assert!(pattern.pattern_at(point).equals(color));
+  }
+}
+#[test]
+fn using_a_planar_mapping_on_a_3d_point() {
+  let examples = [
+    (Tuple::point(0.25, 0.0, 0.5), (0.25, 0.5)),
+    (Tuple::point(0.25, 0.0, -0.25), (0.25, 0.75)),
+    (Tuple::point(0.25, 0.5, -0.25), (0.25, 0.75)),
+    (Tuple::point(1.25, 0.0, 0.5), (0.25, 0.5)),
+    (Tuple::point(0.25, 0.0, -1.75), (0.25, 0.25)),
+    (Tuple::point(1.0, 0.0, -1.0), (0.0, 0.0)),
+    (Tuple::point(0.0, 0.0, 0.0), (0.0, 0.0))
+  ];
+  for (point, uv) in examples {
+    assert_uv(planar_map(point), uv);
+  }
+}
+#[test]
+fn using_a_cylindrical_mapping_on_a_3d_point() {
+  let examples = [
+    (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.0)),
+    (Tuple::point(0.0, 0.5, -1.0), (0.0, 0.5)),
+    (Tuple::point(0.0, 1.0, -1.0), (0.0, 0.0)),
+    (Tuple::point(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), (0.125, 0.5)),
+    (Tuple::point(1.0, 1.25, 0.0), (0.25, 0.25)),
+    (Tuple::point(FRAC_1_SQRT_2, 0.25, FRAC_1_SQRT_2), (0.375, 0.25)),
+    (Tuple::point(0.0, 0.75, 1.0), (0.5, 0.75)),
+    (Tuple::point(-FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2), (0.625, 0.5)),
+    (Tuple::point(-1.0, 1.25, 0.0), (0.75, 0.25)),
+    (Tuple::point(-FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), (0.875, 0.5))
+  ];
+  for (point, uv) in examples {
+    assert_uv(cylindrical_map(point), uv);
+  }
+}
+#[test]
+fn layout_of_the_align_check_pattern() {
+  let main = Tuple::color(1.0, 1.0, 1.0);
+  let ul = Tuple::color(1.0, 0.0, 0.0);
+  let ur = Tuple::color(1.0, 1.0, 0.0);
+  let bl = Tuple::color(0.0, 1.0, 0.0);
+  let br = Tuple::color(0.0, 1.0, 1.0);
+  let pattern = UvAlignCheck::new(main, ul, ur, bl, br);
+  assert!(pattern.uv_pattern_at(0.5, 0.5).equals(main));
+  assert!(pattern.uv_pattern_at(0.1, 0.9).equals(ul));
+  assert!(pattern.uv_pattern_at(0.9, 0.9).equals(ur));
+  assert!(pattern.uv_pattern_at(0.1, 0.1).equals(bl));
+  assert!(pattern.uv_pattern_at(0.9, 0.1).equals(br));
+}
+#[test]
+fn identifying_the_face_of_a_cube_from_a_point() {
+  let examples = [
+    (Tuple::point(-1.0, 0.5, -0.25), Face::Left),
+    (Tuple::point(1.1, -0.75, 0.8), Face::Right),
+    (Tuple::point(0.1, 0.6, 0.9), Face::Front),
+    (Tuple::point(-0.7, 0.0, -2.0), Face::Back),
+    (Tuple::point(0.5, 1.0, 0.9), Face::Up),
+    (Tuple::point(-0.2, -1.3, 1.1), Face::Down)
+  ];
+  for (point, face) in examples {
+    assert_eq!(face_from_point(point), face);
+  }
+}
+#[test]
+fn uv_mapping_each_face_of_a_cube() {
+  let examples = [
+    (Face::Front, Tuple::point(-0.5, 0.5, 1.0), (0.25, 0.75)),
+    (Face::Front, Tuple::point(0.5, -0.5, 1.0), (0.75, 0.25)),
+    (Face::Back, Tuple::point(0.5, 0.5, -1.0), (0.25, 0.75)),
+    (Face::Back, Tuple::point(-0.5, -0.5, -1.0), (0.75, 0.25)),
+    (Face::Left, Tuple::point(-1.0, 0.5, -0.5), (0.25, 0.75)),
+    (Face::Left, Tuple::point(-1.0, -0.5, 0.5), (0.75, 0.25)),
+    (Face::Right, Tuple::point(1.0, 0.5, 0.5), (0.25, 0.75)),
+    (Face::Right, Tuple::point(1.0, -0.5, -0.5), (0.75, 0.25)),
+    (Face::Up, Tuple::point(-0.5, 1.0, -0.5), (0.25, 0.75)),
+    (Face::Up, Tuple::point(0.5, 1.0, 0.5), (0.75, 0.25)),
+    (Face::Down, Tuple::point(-0.5, -1.0, 0.5), (0.25, 0.75)),
+    (Face::Down, Tuple::point(0.5, -1.0, -0.5), (0.75, 0.25))
+  ];
+  for (face, point, uv) in examples {
+    assert_uv(cube_map(face, point), uv);
+  }
+}
+#[test]
+fn finding_the_colors_on_a_mapped_cube() {
+  let red = Tuple::color(1.0, 0.0, 0.0);
+  let yellow = Tuple::color(1.0, 1.0, 0.0);
+  let brown = Tuple::color(1.0, 0.5, 0.0);
+  let green = Tuple::color(0.0, 1.0, 0.0);
+  let cyan = Tuple::color(0.0, 1.0, 1.0);
+  let blue = Tuple::color(0.0, 0.0, 1.0);
+  let purple = Tuple::color(1.0, 0.0, 1.0);
+  let white = Tuple::color(1.0, 1.0, 1.0);
+  let pattern = CubeMap::new(
+    Rc::new(UvAlignCheck::new(yellow, cyan, red, blue, brown)),
+    Rc::new(UvAlignCheck::new(cyan, red, yellow, brown, green)),
+    Rc::new(UvAlignCheck::new(red, yellow, purple, green, white)),
+    Rc::new(UvAlignCheck::new(green, purple, cyan, white, blue)),
+    Rc::new(UvAlignCheck::new(brown, cyan, purple, red, yellow)),
+    Rc::new(UvAlignCheck::new(purple, brown, green, blue, white))
+  );
+  let examples = [
+    // left
+    (Tuple::point(-1.0, 0.0, 0.0), yellow),
+    (Tuple::point(-1.0, 0.9, -0.9), cyan),
+    (Tuple::point(-1.0, 0.9, 0.9), red),
+    (Tuple::point(-1.0, -0.9, -0.9), blue),
+    (Tuple::point(-1.0, -0.9, 0.9), brown),
+    // front
+    (Tuple::point(0.0, 0.0, 1.0), cyan),
+    (Tuple::point(-0.9, 0.9, 1.0), red),
+    (Tuple::point(0.9, 0.9, 1.0), yellow),
+    (Tuple::point(-0.9, -0.9, 1.0), brown),
+    (Tuple::point(0.9, -0.9, 1.0), green),
+    // right
+    (Tuple::point(1.0, 0.0, 0.0), red),
+    (Tuple::point(1.0, 0.9, 0.9), yellow),
+    (Tuple::point(1.0, 0.9, -0.9), purple),
+    (Tuple::point(1.0, -0.9, 0.9), green),
+    (Tuple::point(1.0, -0.9, -0.9), white),
+    // back
+    (Tuple::point(0.0, 0.0, -1.0), green),
+    (Tuple::point(0.9, 0.9, -1.0), purple),
+    (Tuple::point(-0.9, 0.9, -1.0), cyan),
+    (Tuple::point(0.9, -0.9, -1.0), white),
+    (Tuple::point(-0.9, -0.9, -1.0), blue),
+    // up
+    (Tuple::point(0.0, 1.0, 0.0), brown),
+    (Tuple::point(-0.9, 1.0, -0.9), cyan),
+    (Tuple::point(0.9, 1.0, -0.9), purple),
+    (Tuple::point(-0.9, 1.0, 0.9), red),
+    (Tuple::point(0.9, 1.0, 0.9), yellow),
+    // down
+    (Tuple::point(0.0, -1.0, 0.0), purple),
+    (Tuple::point(-0.9, -1.0, 0.9), brown),
+    (Tuple::point(0.9, -1.0, 0.9), green),
+    (Tuple::point(-0.9, -1.0, -0.9), blue),
+    (Tuple::point(0.9, -1.0, -0.9), white)
+  ];
+  for (point, color) in examples {
+    assert!(pattern.pattern_at(point).equals(color));
+  }
+}
+#[test]
+fn a_texture_map_follows_the_object_transform() {
+  let black = Tuple::color(0.0, 0.0, 0.0);
+  let white = Tuple::color(1.0, 1.0, 1.0);
+  let pattern = TextureMap::new(Rc::new(UvCheckers::new(2.0, 2.0, black, white)), Mapping::Planar);
+  let mut shape = Sphere::new();
+  shape.set_transform(Matrix::scaling(2.0, 2.0, 2.0));
+  // (1.5, 0, 0.5) in world space is (0.75, 0, 0.25) on the unit sphere
+  assert!(pattern.pattern_at_shape(&shape, Tuple::point(1.5, 0.0, 0.5)).equals(white));
+  assert!(pattern.pattern_at_shape(&shape, Tuple::point(1.5, 0.0, 1.5)).equals(black));
+}
+#[test]
+fn a_texture_map_with_a_cylindrical_mapping() {
+  let black = Tuple::color(0.0, 0.0, 0.0);
+  let white = Tuple::color(1.0, 1.0, 1.0);
+  let pattern = TextureMap::new(Rc::new(UvCheckers::new(2.0, 2.0, black, white)), Mapping::Cylindrical);
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.25, -1.0)).equals(black));
+  assert!(pattern.pattern_at(Tuple::point(1.0, 0.25, 0.0)).equals(black));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.25, 1.0)).equals(white));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 0.75, -1.0)).equals(white));
+  assert!(pattern.pattern_at(Tuple::point(0.0, 1.75, 1.0)).equals(black));
+}
@@ -0,0 +1,443 @@
+use crate::tuple::Tuple;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::lights::PointLight;
+use crate::materials::lighting;
+use crate::intersection::{Computations, Intersections};
+#[cfg(test)]
+use crate::matrix::Matrix;
+#[cfg(test)]
+use crate::sphere::Sphere;
+#[cfg(test)]
+use crate::materials::Material;
+#[cfg(test)]
+use crate::intersection::Intersection;
+#[cfg(test)]
+use crate::plane::Plane;
+#[cfg(test)]
+use crate::patterns::TestPattern;
+#[cfg(test)]
+use std::rc::Rc;
+#[cfg(test)]
+use crate::group::Group;
+#[cfg(test)]
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+// how many bounces a ray gets before reflections give up and return black
+pub const MAX_DEPTH: usize = 5;
+
+pub struct World{ pub objects: Vec<Box<dyn Shape>>, pub lights: Vec<PointLight> }
+impl World {
+  pub fn new() -> Self {
+    World{objects: vec![], lights: vec![]}
+  }
+  // indexes the children of every group in the scene, done once it's assembled and before rendering
+  pub fn build_bvh(&mut self) {
+    for object in self.objects.iter_mut() {
+      object.build_bvh();
+    }
+  }
+  // every object's intersections merged into one t-sorted list
+  pub fn intersect(&self, ray: &Ray) -> Intersections<'_> {
+    let mut list = vec![];
+    for object in self.objects.iter() {
+      list.extend(object.intersect(ray));
+    }
+    Intersections::new(list)
+  }
+  pub fn shade_hit(&self, comps: &Computations, remaining: usize) -> Tuple {
+    let mut surface = Tuple::color(0.0, 0.0, 0.0);
+    for light in self.lights.iter() {
+      let in_shadow = self.is_shadowed(comps.over_point, light);
+      surface = surface + lighting(comps.object.material(), comps.object, light, comps.over_point, comps.eyev, comps.normalv, in_shadow);
+    }
+    let reflected = self.reflected_color(comps, remaining);
+    let refracted = self.refracted_color(comps, remaining);
+    let material = comps.object.material();
+    if material.reflective > 0.0 && material.transparency > 0.0 {
+      let reflectance = comps.schlick();
+      return surface + reflected * reflectance + refracted * (1.0 - reflectance);
+    }
+    surface + reflected + refracted
+  }
+  // remaining counts down with each bounce so facing mirrors can't recurse forever
+  pub fn reflected_color(&self, comps: &Computations, remaining: usize) -> Tuple {
+    let reflective = comps.object.material().reflective;
+    if remaining == 0 || reflective == 0.0 {
+      return Tuple::color(0.0, 0.0, 0.0);
+    }
+    let reflect_ray = Ray::new(comps.over_point, comps.reflectv);
+    self.color_at(&reflect_ray, remaining - 1) * reflective
+  }
+  // bends the eye ray through the surface by snell's law and follows it from just under the point
+  pub fn refracted_color(&self, comps: &Computations, remaining: usize) -> Tuple {
+    let transparency = comps.object.material().transparency;
+    if remaining == 0 || transparency == 0.0 {
+      return Tuple::color(0.0, 0.0, 0.0);
+    }
+    let n_ratio = comps.n1 / comps.n2;
+    let cos_i = comps.eyev.dot(comps.normalv);
+    let sin2_t = n_ratio * n_ratio * (1.0 - cos_i * cos_i);
+    // total internal reflection, nothing gets through
+    if sin2_t > 1.0 {
+      return Tuple::color(0.0, 0.0, 0.0);
+    }
+    let cos_t = f64::sqrt(1.0 - sin2_t);
+    let direction = comps.normalv * (n_ratio * cos_i - cos_t) - comps.eyev * n_ratio;
+    let refract_ray = Ray::new(comps.under_point, direction);
+    self.color_at(&refract_ray, remaining - 1) * transparency
+  }
+  // true when something sits between the point and the light
+  pub fn is_shadowed(&self, point: Tuple, light: &PointLight) -> bool {
+    let v = light.position - point;
+    let distance = v.magnitude();
+    let ray = Ray::new(point, v.normalize());
+    match self.intersect(&ray).hit() {
+      Some(hit) => hit.t < distance,
+      None => false
+    }
+  }
+  pub fn color_at(&self, ray: &Ray, remaining: usize) -> Tuple {
+    let xs = self.intersect(ray);
+    match xs.hit() {
+      Some(hit) => self.shade_hit(&hit.prepare_computations(ray, &xs), remaining),
+      None => Tuple::color(0.0, 0.0, 0.0)
+    }
+  }
+}
+impl Default for World {
+  fn default() -> Self {
+    World::new()
+  }
+}
+
+// the two concentric spheres and single light most world tests start from
+#[cfg(test)]
+pub fn default_world() -> World {
+  let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Tuple::color(1.0, 1.0, 1.0));
+  let mut s1 = Sphere::new();
+  let mut m = Material::new();
+  m.color = Tuple::color(0.8, 1.0, 0.6);
+  m.diffuse = 0.7;
+  m.specular = 0.2;
+  s1.set_material(m);
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::scaling(0.5, 0.5, 0.5));
+  World{objects: vec![Box::new(s1), Box::new(s2)], lights: vec![light]}
+}
+
+#[test]
+fn creating_a_world() {
+  let w = World::new();
+  assert_eq!(w.objects.len(), 0);
+  assert_eq!(w.lights.len(), 0);
+}
+#[test]
+fn the_default_world() {
+  let w = default_world();
+  assert_eq!(w.objects.len(), 2);
+  assert!(w.lights[0].position.equals(Tuple::point(-10.0, 10.0, -10.0)));
+  assert!(w.objects[0].material().color.equals(Tuple::color(0.8, 1.0, 0.6)));
+  assert!(w.objects[1].transform().equals(Matrix::scaling(0.5, 0.5, 0.5)));
+}
+#[test]
+fn intersect_a_world_with_a_ray() {
+  let w = default_world();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = w.intersect(&r);
+  assert_eq!(xs.len(), 4);
+  assert_eq!(xs[0].t, 4.0);
+  assert_eq!(xs[1].t, 4.5);
+  assert_eq!(xs[2].t, 5.5);
+  assert_eq!(xs[3].t, 6.0);
+}
+#[test]
+fn shading_an_intersection() {
+  let w = default_world();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let shape = w.objects[0].as_ref();
+  let i = Intersection::new(4.0, shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let c = w.shade_hit(&comps, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.38066, 0.47583, 0.2855)));
+}
+#[test]
+fn shading_an_intersection_from_the_inside() {
+  let mut w = default_world();
+  w.lights = vec![PointLight::new(Tuple::point(0.0, 0.25, 0.0), Tuple::color(1.0, 1.0, 1.0))];
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let shape = w.objects[1].as_ref();
+  let i = Intersection::new(0.5, shape);
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let c = w.shade_hit(&comps, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.90498, 0.90498, 0.90498)));
+}
+#[test]
+fn shading_with_more_than_one_light_adds_their_contributions() {
+  let mut w = default_world();
+  let light = w.lights[0];
+  w.lights.push(light);
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let c = w.color_at(&r, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.38066, 0.47583, 0.2855) * 2.0));
+}
+#[test]
+fn the_color_when_a_ray_misses() {
+  let w = default_world();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+  let c = w.color_at(&r, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+#[test]
+fn the_color_when_a_ray_hits() {
+  let w = default_world();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let c = w.color_at(&r, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.38066, 0.47583, 0.2855)));
+}
+#[test]
+fn the_color_with_an_intersection_behind_the_ray() {
+  let mut w = default_world();
+  let mut m = w.objects[0].material().clone();
+  m.ambient = 1.0;
+  w.objects[0].set_material(m);
+  let mut m = w.objects[1].material().clone();
+  m.ambient = 1.0;
+  w.objects[1].set_material(m);
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.75), Tuple::vector(0.0, 0.0, -1.0));
+  let c = w.color_at(&r, MAX_DEPTH);
+  assert!(c.equals(w.objects[1].material().color));
+}
+#[test]
+fn there_is_no_shadow_when_nothing_is_collinear_with_point_and_light() {
+  let w = default_world();
+  let p = Tuple::point(0.0, 10.0, 0.0);
+  assert!(!w.is_shadowed(p, &w.lights[0]));
+}
+#[test]
+fn the_shadow_when_an_object_is_between_the_point_and_the_light() {
+  let w = default_world();
+  let p = Tuple::point(10.0, -10.0, 10.0);
+  assert!(w.is_shadowed(p, &w.lights[0]));
+}
+#[test]
+fn there_is_no_shadow_when_an_object_is_behind_the_light() {
+  let w = default_world();
+  let p = Tuple::point(-20.0, 20.0, -20.0);
+  assert!(!w.is_shadowed(p, &w.lights[0]));
+}
+#[test]
+fn there_is_no_shadow_when_an_object_is_behind_the_point() {
+  let w = default_world();
+  let p = Tuple::point(-2.0, 2.0, -2.0);
+  assert!(!w.is_shadowed(p, &w.lights[0]));
+}
+#[test]
+fn shade_hit_is_given_an_intersection_in_shadow() {
+  let mut w = World::new();
+  w.lights = vec![PointLight::new(Tuple::point(0.0, 0.0, -10.0), Tuple::color(1.0, 1.0, 1.0))];
+  let s1 = Sphere::new();
+  let mut s2 = Sphere::new();
+  s2.set_transform(Matrix::translation(0.0, 0.0, 10.0));
+  w.objects = vec![Box::new(s1), Box::new(s2)];
+  let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let i = Intersection::new(4.0, w.objects[1].as_ref());
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let c = w.shade_hit(&comps, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.1, 0.1, 0.1)));
+}
+#[test]
+fn the_reflected_color_for_a_nonreflective_material() {
+  let mut w = default_world();
+  let mut m = w.objects[1].material().clone();
+  m.ambient = 1.0;
+  w.objects[1].set_material(m);
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+  let i = Intersection::new(1.0, w.objects[1].as_ref());
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let color = w.reflected_color(&comps, MAX_DEPTH);
+  assert!(color.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+
+// the default world plus a half mirror floor just below the spheres
+#[cfg(test)]
+fn world_with_reflective_floor() -> World {
+  let mut w = default_world();
+  let mut shape = Plane::new();
+  let mut m = Material::new();
+  m.reflective = 0.5;
+  shape.set_material(m);
+  shape.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+  w.objects.push(Box::new(shape));
+  w
+}
+#[test]
+fn the_reflected_color_for_a_reflective_material() {
+  let w = world_with_reflective_floor();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+  let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let color = w.reflected_color(&comps, MAX_DEPTH);
+  assert!((color - Tuple::color(0.19032, 0.2379, 0.14274)).magnitude() < 0.0001);
+}
+#[test]
+fn shade_hit_with_a_reflective_material() {
+  let w = world_with_reflective_floor();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+  let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let color = w.shade_hit(&comps, MAX_DEPTH);
+  assert!((color - Tuple::color(0.87677, 0.92436, 0.82918)).magnitude() < 0.0001);
+}
+#[test]
+fn color_at_with_mutually_reflective_surfaces() {
+  let mut w = World::new();
+  w.lights = vec![PointLight::new(Tuple::point(0.0, 0.0, 0.0), Tuple::color(1.0, 1.0, 1.0))];
+  let mut m = Material::new();
+  m.reflective = 1.0;
+  let mut lower = Plane::new();
+  lower.set_material(m.clone());
+  lower.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+  let mut upper = Plane::new();
+  upper.set_material(m);
+  upper.set_transform(Matrix::translation(0.0, 1.0, 0.0));
+  w.objects = vec![Box::new(lower), Box::new(upper)];
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+  // only needs to terminate
+  w.color_at(&r, MAX_DEPTH);
+}
+#[test]
+fn the_reflected_color_at_the_maximum_recursive_depth() {
+  let w = world_with_reflective_floor();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+  let i = Intersection::new(SQRT_2, w.objects[2].as_ref());
+  let comps = i.prepare_computations(&r, &Intersections::new(vec![i]));
+  let color = w.reflected_color(&comps, 0);
+  assert!(color.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+#[test]
+fn the_refracted_color_with_an_opaque_surface() {
+  let w = default_world();
+  let shape = w.objects[0].as_ref();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = Intersections::new(vec![Intersection::new(4.0, shape), Intersection::new(6.0, shape)]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  let c = w.refracted_color(&comps, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+#[test]
+fn the_refracted_color_at_the_maximum_recursive_depth() {
+  let mut w = default_world();
+  let mut m = w.objects[0].material().clone();
+  m.transparency = 1.0;
+  m.refractive_index = 1.5;
+  w.objects[0].set_material(m);
+  let shape = w.objects[0].as_ref();
+  let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+  let xs = Intersections::new(vec![Intersection::new(4.0, shape), Intersection::new(6.0, shape)]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  let c = w.refracted_color(&comps, 0);
+  assert!(c.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+#[test]
+fn the_refracted_color_under_total_internal_reflection() {
+  let mut w = default_world();
+  let mut m = w.objects[0].material().clone();
+  m.transparency = 1.0;
+  m.refractive_index = 1.5;
+  w.objects[0].set_material(m);
+  let shape = w.objects[0].as_ref();
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, v), Tuple::vector(0.0, 1.0, 0.0));
+  let xs = Intersections::new(vec![Intersection::new(-v, shape), Intersection::new(v, shape)]);
+  // inside the sphere, so look at the second intersection
+  let comps = xs[1].prepare_computations(&r, &xs);
+  let c = w.refracted_color(&comps, MAX_DEPTH);
+  assert!(c.equals(Tuple::color(0.0, 0.0, 0.0)));
+}
+#[test]
+fn the_refracted_color_with_a_refracted_ray() {
+  let mut w = default_world();
+  let mut m = w.objects[0].material().clone();
+  m.ambient = 1.0;
+  m.pattern = Some(Rc::new(TestPattern::new()));
+  w.objects[0].set_material(m);
+  let mut m = w.objects[1].material().clone();
+  m.transparency = 1.0;
+  m.refractive_index = 1.5;
+  w.objects[1].set_material(m);
+  let a = w.objects[0].as_ref();
+  let b = w.objects[1].as_ref();
+  let r = Ray::new(Tuple::point(0.0, 0.0, 0.1), Tuple::vector(0.0, 1.0, 0.0));
+  let xs = Intersections::new(vec![
+    Intersection::new(-0.9899, a),
+    Intersection::new(-0.4899, b),
+    Intersection::new(0.4899, b),
+    Intersection::new(0.9899, a)
+  ]);
+  let comps = xs[2].prepare_computations(&r, &xs);
+  let c = w.refracted_color(&comps, MAX_DEPTH);
+  assert!((c - Tuple::color(0.0, 0.99888, 0.04725)).magnitude() < 0.0001);
+}
+
+// the default world over a glass floor with a red ball sunk below it
+#[cfg(test)]
+fn world_with_glass_floor(reflective: f64) -> World {
+  let mut w = default_world();
+  let mut floor = Plane::new();
+  floor.set_transform(Matrix::translation(0.0, -1.0, 0.0));
+  let mut m = Material::new();
+  m.reflective = reflective;
+  m.transparency = 0.5;
+  m.refractive_index = 1.5;
+  floor.set_material(m);
+  let mut ball = Sphere::new();
+  let mut m = Material::new();
+  m.color = Tuple::color(1.0, 0.0, 0.0);
+  m.ambient = 0.5;
+  ball.set_material(m);
+  ball.set_transform(Matrix::translation(0.0, -3.5, -0.5));
+  w.objects.push(Box::new(floor));
+  w.objects.push(Box::new(ball));
+  w
+}
+#[test]
+fn shade_hit_with_a_transparent_material() {
+  let w = world_with_glass_floor(0.0);
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+  let xs = Intersections::new(vec![Intersection::new(SQRT_2, w.objects[2].as_ref())]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  let color = w.shade_hit(&comps, MAX_DEPTH);
+  assert!((color - Tuple::color(0.93642, 0.68642, 0.68642)).magnitude() < 0.0001);
+}
+#[test]
+fn shade_hit_with_a_reflective_transparent_material() {
+  let w = world_with_glass_floor(0.5);
+  let v = FRAC_1_SQRT_2;
+  let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, -v, v));
+  let xs = Intersections::new(vec![Intersection::new(SQRT_2, w.objects[2].as_ref())]);
+  let comps = xs[0].prepare_computations(&r, &xs);
+  let color = w.shade_hit(&comps, MAX_DEPTH);
+  assert!((color - Tuple::color(0.93391, 0.69643, 0.69243)).magnitude() < 0.0001);
+}
+#[test]
+fn building_the_bvh_keeps_every_hit() {
+  let mut g = Group::new();
+  for i in 0..16 {
+    let mut s = Sphere::new();
+    s.set_transform(Matrix::translation(i as f64 * 3.0, 0.0, 0.0));
+    g.add_child(Box::new(s));
+  }
+  let mut w = World::new();
+  w.objects.push(Box::new(g));
+  let r = Ray::new(Tuple::point(-5.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+  let before: Vec<f64> = w.intersect(&r).into_iter().map(|i| i.t).collect();
+  w.build_bvh();
+  let after: Vec<f64> = w.intersect(&r).into_iter().map(|i| i.t).collect();
+  assert_eq!(before.len(), 32);
+  assert_eq!(before, after);
+}
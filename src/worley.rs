@@ -0,0 +1,168 @@
+use crate::tuple::Tuple;
+use crate::noise::DEFAULT_SEED;
+
+// how the distance from a lookup point to a feature point is measured
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Metric {
+  Euclidean,
+  Manhattan,
+  Chebyshev
+}
+impl Metric {
+  pub fn distance(&self, v: Tuple) -> f64 {
+    match self {
+      Metric::Euclidean => f64::sqrt(v.x * v.x + v.y * v.y + v.z * v.z),
+      Metric::Manhattan => v.x.abs() + v.y.abs() + v.z.abs(),
+      Metric::Chebyshev => v.x.abs().max(v.y.abs()).max(v.z.abs())
+    }
+  }
+}
+
+// which of the nearest distances the noise reports. f1 gives round cells,
+// f2 puffier ones and f2 - f1 is zero along the borders between cells
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Feature {
+  F1,
+  F2,
+  F2MinusF1
+}
+
+// cellular noise: one randomly placed feature point in every unit cube,
+// the same seed always places them in the same spots
+#[derive(Clone)]
+pub struct Worley{ seed: u64, pub metric: Metric }
+impl Worley {
+  pub fn new() -> Self {
+    Worley::with_seed(DEFAULT_SEED)
+  }
+  pub fn with_seed(seed: u64) -> Self {
+    Worley{seed, metric: Metric::Euclidean}
+  }
+  // distances to the nearest and second nearest feature points
+  pub fn distances(&self, point: Tuple) -> (f64, f64) {
+    let (cx, cy, cz) = (point.x.floor() as i64, point.y.floor() as i64, point.z.floor() as i64);
+    let mut f1 = f64::INFINITY;
+    let mut f2 = f64::INFINITY;
+    // with one point per cell the nearest two are almost always in the surrounding 3x3x3 block,
+    // looking further out costs far more than the rare miss is worth
+    for dx in -1..=1 {
+      for dy in -1..=1 {
+        for dz in -1..=1 {
+          let feature = self.feature_point(cx + dx, cy + dy, cz + dz);
+          let d = self.metric.distance(feature - point);
+          if d < f1 {
+            f2 = f1;
+            f1 = d;
+          }
+          else if d < f2 {
+            f2 = d;
+          }
+        }
+      }
+    }
+    (f1, f2)
+  }
+  pub fn value(&self, point: Tuple, feature: Feature) -> f64 {
+    let (f1, f2) = self.distances(point);
+    match feature {
+      Feature::F1 => f1,
+      Feature::F2 => f2,
+      Feature::F2MinusF1 => f2 - f1
+    }
+  }
+  // the feature point of cell x, y, z, somewhere inside that cell
+  fn feature_point(&self, x: i64, y: i64, z: i64) -> Tuple {
+    let mut h = self.seed;
+    for n in [x, y, z] {
+      h = splitmix(h ^ n as u64);
+    }
+    let fx = unit(h);
+    let fy = unit(splitmix(h));
+    let fz = unit(splitmix(splitmix(h)));
+    Tuple::point(x as f64 + fx, y as f64 + fy, z as f64 + fz)
+  }
+}
+impl Default for Worley {
+  fn default() -> Self {
+    Worley::new()
+  }
+}
+
+// splitmix64, scrambles every bit of the input into every bit of the output
+fn splitmix(n: u64) -> u64 {
+  let mut z = n.wrapping_add(0x9e3779b97f4a7c15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+  z ^ (z >> 31)
+}
+
+// the top 53 bits as a float in 0..1
+fn unit(n: u64) -> f64 {
+  (n >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[test]
+fn measuring_distances_with_each_metric() {
+  let v = Tuple::vector(1.0, -2.0, 3.0);
+  assert_eq!(Metric::Euclidean.distance(v), f64::sqrt(14.0));
+  assert_eq!(Metric::Manhattan.distance(v), 6.0);
+  assert_eq!(Metric::Chebyshev.distance(v), 3.0);
+}
+#[test]
+fn feature_points_stay_inside_their_cell() {
+  let worley = Worley::new();
+  for (x, y, z) in [(0, 0, 0), (-3, 5, 2), (100, -100, 7)] {
+    let p = worley.feature_point(x, y, z);
+    assert!(p.x >= x as f64 && p.x < x as f64 + 1.0);
+    assert!(p.y >= y as f64 && p.y < y as f64 + 1.0);
+    assert!(p.z >= z as f64 && p.z < z as f64 + 1.0);
+  }
+}
+#[test]
+fn f1_is_zero_on_a_feature_point() {
+  let worley = Worley::new();
+  let p = worley.feature_point(2, -1, 4);
+  assert_eq!(worley.value(p, Feature::F1), 0.0);
+  assert!(worley.value(p, Feature::F2) > 0.0);
+}
+#[test]
+fn the_features_are_ordered() {
+  let worley = Worley::new();
+  for i in 0..200 {
+    let t = i as f64 * 0.137;
+    let p = Tuple::point(t, 1.5 - t * 0.3, t * 0.7);
+    let (f1, f2) = worley.distances(p);
+    assert!(f1 <= f2);
+    assert_eq!(worley.value(p, Feature::F1), f1);
+    assert_eq!(worley.value(p, Feature::F2), f2);
+    assert_eq!(worley.value(p, Feature::F2MinusF1), f2 - f1);
+  }
+}
+#[test]
+fn the_metrics_bound_each_other() {
+  let mut worley = Worley::new();
+  for i in 0..100 {
+    let t = i as f64 * 0.211;
+    let p = Tuple::point(t, -t, 3.0 + t * 0.5);
+    worley.metric = Metric::Chebyshev;
+    let chebyshev = worley.value(p, Feature::F1);
+    worley.metric = Metric::Euclidean;
+    let euclidean = worley.value(p, Feature::F1);
+    worley.metric = Metric::Manhattan;
+    let manhattan = worley.value(p, Feature::F1);
+    assert!(chebyshev <= euclidean && euclidean <= manhattan);
+  }
+}
+#[test]
+fn worley_noise_follows_the_seed() {
+  let a = Worley::with_seed(3);
+  let b = Worley::with_seed(3);
+  let c = Worley::with_seed(4);
+  let mut differs = false;
+  for i in 0..50 {
+    let p = Tuple::point(i as f64 * 0.31, 0.2, i as f64 * -0.17);
+    assert_eq!(a.distances(p), b.distances(p));
+    differs |= a.distances(p) != c.distances(p);
+  }
+  assert!(differs);
+}